@@ -0,0 +1,102 @@
+// Benchmarks of the board's hot paths, for measuring redesigns of the board. Run with `cargo bench --bench board`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use schotten::board::cards_on_board::stonecards::StoneCards;
+use schotten::board::cards_on_board::CardsOnBoard;
+use schotten::components::{Card, Player, Stone};
+use schotten::Game;
+
+fn stone_cards(cards: &[(u8, u8)]) -> StoneCards {
+    let mut stone_cards = StoneCards::new();
+
+    for (num, color) in cards {
+        stone_cards.push(Card::build(*num, *color));
+    }
+
+    stone_cards
+}
+
+// A game after `plies` random moves, with every legal claim made.
+fn midgame(plies: usize) -> Game {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut game = Game::with_rng(&mut rng);
+
+    for _ in 0..plies {
+        schotten::search::begin_turn(&mut game);
+
+        let actions = game.legal_actions();
+
+        if actions.is_empty() {
+            game.pass();
+        } else {
+            game.apply_action(actions[rng.gen_range(0..actions.len())]);
+        }
+    }
+
+    game
+}
+
+fn strength(c: &mut Criterion) {
+    let formations = [
+        ("color run", stone_cards(&[(7, 1), (8, 1), (9, 1)])),
+        ("three of a kind", stone_cards(&[(2, 1), (2, 3), (2, 5)])),
+        ("color", stone_cards(&[(1, 2), (6, 2), (2, 2)])),
+        ("run", stone_cards(&[(5, 1), (3, 4), (4, 6)])),
+        ("sum", stone_cards(&[(9, 1), (2, 4), (4, 6)])),
+    ];
+    let mut group = c.benchmark_group("StoneCards::strength");
+
+    for (name, cards) in formations.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), cards, |b, cards| b.iter(|| black_box(cards).strength()));
+    }
+
+    group.finish();
+}
+
+fn legal_claim(c: &mut Criterion) {
+    // A color run on an otherwise empty board, which no completion of the opponent's cards beats.
+    // Proving the claim takes every completion, the most a check can take for each opponent card count.
+    // Measured through `is_legal_claim`, which only adds a lookup of the claims.
+    let player = Player::build(0);
+    let stone = Stone::build(4);
+    let opponent_cards = [Card::build(1, 2), Card::build(2, 4), Card::build(4, 6)];
+    let mut group = c.benchmark_group("proto_is_legal_claim");
+
+    for count in 0..=opponent_cards.len() {
+        let mut board = CardsOnBoard::new();
+
+        for num in 7..=9 {
+            board.place_card(player, stone, Card::build(num, 1));
+        }
+        for card in opponent_cards[..count].iter() {
+            board.place_card(player.get_other(), stone, card.clone());
+        }
+
+        assert!(board.is_legal_claim(player, stone));
+
+        group.bench_with_input(BenchmarkId::new("opponent cards", count), &board, |b, board| {
+            b.iter(|| black_box(board).is_legal_claim(player, stone))
+        });
+    }
+
+    group.finish();
+}
+
+fn available_stones(c: &mut Criterion) {
+    let mut group = c.benchmark_group("available_stones_for");
+
+    for plies in [0, 20, 40] {
+        let game = midgame(plies);
+
+        group.bench_with_input(BenchmarkId::new("plies", plies), game.board(), |b, board| {
+            b.iter(|| black_box(board).available_stones_for(Player::build(0)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, strength, legal_claim, available_stones);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+// Benchmarks of whole games and searches, reported per playout and per search iteration.
+// Run with `cargo bench --bench search`.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rand::{rngs::StdRng, SeedableRng};
+
+use schotten::player::random_player::RandomPlayer;
+use schotten::search::playout::Playout;
+use schotten::search::rollout::UniformRollout;
+use schotten::search::{ismcts, uct::{self, UctConfig}};
+use schotten::Game;
+
+const ITERATIONS: u32 = 1000;
+
+fn playouts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("playouts");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("random players from Game::new", |b| {
+        b.iter(|| Game::new().play(RandomPlayer, RandomPlayer))
+    });
+
+    let mut rng = StdRng::seed_from_u64(0);
+
+    group.bench_function("uniform rollout", |b| {
+        b.iter(|| schotten::search::rollout(&mut Game::new(), &UniformRollout, &mut rng))
+    });
+
+    let playout = Playout::new(&Game::new());
+
+    group.bench_function("allocation free playout", |b| b.iter(|| playout.play(&mut rng)));
+
+    group.finish();
+}
+
+fn searches(c: &mut Criterion) {
+    // A single tree without the endgame solver, so that every iteration is a tree walk and a rollout.
+    let config = UctConfig { iterations: ITERATIONS, determinizations: 1, endgame_nodes: 0, rollout: Arc::new(UniformRollout), ..UctConfig::default() };
+    let game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+    let observation = game.observation(game.to_move());
+    let mut rng = StdRng::seed_from_u64(1);
+
+    let mut group = c.benchmark_group("search iterations");
+    group.throughput(Throughput::Elements(ITERATIONS as u64));
+    group.sample_size(20);
+
+    group.bench_function("uct", |b| b.iter(|| uct::search(&observation, &config, &mut rng)));
+    group.bench_function("ismcts", |b| b.iter(|| ismcts::search(&observation, &config, &mut rng)));
+
+    group.finish();
+}
+
+criterion_group!(benches, playouts, searches);
+criterion_main!(benches);
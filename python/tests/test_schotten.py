@@ -0,0 +1,129 @@
+import random
+import unittest
+
+import schotten
+
+
+def play_out(game, rng, bot=None):
+    while game.winner() is None:
+        for stone in range(9):
+            game.claim(stone)
+
+        if game.winner() is not None:
+            break
+
+        actions = game.legal_actions()
+
+        if not actions:
+            game.pass_turn()
+        elif bot is not None:
+            game.apply_action(*game.bot_action(bot))
+        else:
+            game.apply_action(*rng.choice(actions))
+
+    return game.winner()
+
+
+class TestGame(unittest.TestCase):
+    def test_seeded_games_are_equal(self):
+        self.assertEqual(str(schotten.Game(seed=4)), str(schotten.Game(seed=4)))
+        self.assertEqual(schotten.Game(seed=4).hand(0), schotten.Game(seed=4).hand(0))
+
+    def test_new_game(self):
+        game = schotten.Game(seed=1)
+
+        self.assertEqual(game.to_move(), 0)
+        self.assertIsNone(game.winner())
+        self.assertEqual(game.deck_len(), 42)
+        self.assertEqual(len(game.hand(0)), 6)
+        self.assertEqual(len(game.legal_actions()), 6 * 9)
+
+    def test_apply_action(self):
+        game = schotten.Game(seed=2)
+        card = game.hand(0)[3]
+
+        game.apply_action(3, 8)
+
+        self.assertEqual(game.to_move(), 1)
+        self.assertEqual(game.observation(0)["cards"][0][8], [card])
+        self.assertNotIn(card, game.hand(0))
+        self.assertEqual(game.deck_len(), 41)
+
+    def test_illegal_action(self):
+        game = schotten.Game(seed=2)
+
+        with self.assertRaises(ValueError):
+            game.apply_action(6, 0)
+        with self.assertRaises(ValueError):
+            game.apply_action(0, 9)
+        with self.assertRaises(ValueError):
+            game.pass_turn()
+
+    def test_claims(self):
+        game = schotten.Game(seed=3)
+
+        for stone in range(9):
+            self.assertFalse(game.is_legal_claim(0, stone))
+            self.assertFalse(game.claim(stone))
+
+        self.assertEqual(game.claimable_stones(0), [])
+
+        with self.assertRaises(ValueError):
+            game.is_legal_claim(2, 0)
+
+    def test_observation(self):
+        game = schotten.Game(seed=5)
+        game.apply_action(0, 0)
+
+        observation = game.observation(1)
+
+        self.assertEqual(observation["player"], 1)
+        self.assertEqual(observation["hand"], game.hand(1))
+        self.assertEqual(observation["claims"], [None] * 9)
+        self.assertEqual(observation["deck_len"], 41)
+        self.assertEqual(observation["opponent_hand_len"], 6)
+        self.assertEqual(len(observation["unseen"]), 47)
+        self.assertTrue(set(game.hand(0)) <= set(observation["unseen"]))
+
+    def test_encode(self):
+        game = schotten.Game(seed=6)
+        encoding = game.encode(0)
+
+        self.assertEqual(len(encoding), schotten.ENCODING_LEN)
+        self.assertEqual(sum(encoding[:9 * 54 * 2]), 0)
+
+        mask = game.legal_action_mask()
+
+        self.assertEqual(len(mask), schotten.NUM_OF_ACTIONS)
+
+        for hand_index, stone in game.legal_actions():
+            index = schotten.action_index(hand_index, stone)
+
+            self.assertEqual(mask[index], 1.0)
+            self.assertEqual(schotten.action_from_index(index), (hand_index, stone))
+
+        with self.assertRaises(ValueError):
+            schotten.action_index(6, 0)
+        with self.assertRaises(ValueError):
+            schotten.action_index(0, 9)
+        with self.assertRaises(ValueError):
+            schotten.action_from_index(schotten.NUM_OF_ACTIONS)
+
+    def test_random_games(self):
+        rng = random.Random(0)
+
+        for seed in range(5):
+            self.assertIn(play_out(schotten.Game(seed=seed), rng), (0, 1))
+
+    def test_bots(self):
+        self.assertIn("random", schotten.bot_names())
+
+        for bot in schotten.bot_names():
+            self.assertIn(play_out(schotten.Game(seed=7), None, bot), (0, 1))
+
+        with self.assertRaises(ValueError):
+            schotten.Game().bot_action("nobody")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,15 +1,19 @@
-mod stonecards;
+pub mod analysis;
+pub mod stonecards;
+pub mod tables;
 mod advantage;
 mod claims;
+#[allow(dead_code)] // Work in progress, see README.
 mod jobs;
 
 use std::fmt;
 
 use advantage::Advantage;
 use crate::common::{NUM_OF_STONES, NUM_OF_PLAYERS, NUM_OF_COLORS, NUM_OF_NUMS, STONE_CARDS_LIMIT, SPACE, STONE_STR};
-use crate::components::{Player, Stone, Card};
+use crate::components::{Player, Stone, Card, CardMask};
 use claims::Claims;
-use stonecards::StoneCards;
+use stonecards::{Formation, StoneCards};
+use crate::zobrist;
 
 use itertools::Itertools;
 
@@ -22,6 +26,7 @@ mod tests {
     use rand::Rng;
 
     #[test]
+    #[allow(clippy::redundant_closure)]
     fn cards_on_board_place_card_good() {
         let mut board = CardsOnBoard::new();
 
@@ -121,6 +126,27 @@ mod tests {
         available_stones_rec(Rc::new(RefCell::new(Claims::new())), 0);
     }
 
+    #[test]
+    fn cards_on_board_claimable_stones() {
+        let mut board = CardsOnBoard::new();
+        let player = Player::build(1);
+
+        assert!(board.claimable_stones(player).is_empty());
+
+        // A 7-8-9 color run can always be claimed, a full stone tied without the advantage cannot.
+        for num in 7..=9 { board.place_card(player, Stone::build(2), Card::build(num, 1)); }
+        for num in 7..=9 { board.place_card(player, Stone::build(6), Card::build(num, 2)); }
+        for num in 1..=3 { board.place_card(player.get_other(), Stone::build(5), Card::build(num, 3)); }
+        for num in 1..=3 { board.place_card(player, Stone::build(5), Card::build(num, 4)); }
+
+        assert_eq!(board.claimable_stones(player), vec![Stone::build(2), Stone::build(6)]);
+        assert_eq!(board.claimable_stones(player.get_other()), vec![Stone::build(5)]);
+
+        assert!(board.claim(player, Stone::build(2)));
+
+        assert_eq!(board.claimable_stones(player), vec![Stone::build(6)]);
+    }
+
     #[test]
     fn present_cards_add_card() {
         let mut present_cards = PresentCards::new();
@@ -200,6 +226,7 @@ mod test_proto_legal_claim {
 
 
     #[test]
+    #[allow(clippy::redundant_closure)]
     fn test_less_than_three() {
         let mut board = CardsOnBoard::new();
 
@@ -244,6 +271,7 @@ mod test_proto_legal_claim {
         }
     }
 
+    #[allow(clippy::ptr_arg)]
     fn compare(player: Player, vec1: &Vec<Card>, vec2: &Vec<Card>, first_has_advantage: bool) -> bool {
         assert!(vec1.len() == vec2.len());
 
@@ -277,6 +305,7 @@ mod test_proto_legal_claim {
     }
 
     #[test]
+    #[allow(clippy::vec_init_then_push, clippy::clone_on_copy)]
     fn test_winning_hands() {
         let sum_low = vec![Card::build(1,1), Card::build(1,2), Card::build(2,1)]; // 0
         let sum_high = vec![Card::build(9,1), Card::build(9,2), Card::build(8,2)]; // 1
@@ -366,6 +395,120 @@ mod test_proto_legal_claim {
         }
     }
 
+    #[test]
+    fn test_ties_against_incomplete() {
+        // The player completed first, so the other player can at best tie with a 7-8-9 of their own color.
+        let player = Player::build(0);
+        let stone = Stone::build(3);
+
+        for other_cards in [vec![], vec![Card::build(7,2)], vec![Card::build(7,2), Card::build(8,2)]] {
+            let mut board = CardsOnBoard::new();
+
+            for num in 7..=9 { board.place_card(player, stone, Card::build(num, 1)); }
+            for card in other_cards { board.place_card(player.get_other(), stone, card); }
+
+            assert!(board.proto_is_legal_claim(player, stone));
+        }
+
+        // Only a color of the same sum is left to the other player: 1-3-5 against 1-2-6.
+        let mut board = CardsOnBoard::new();
+
+        for num in [1, 2, 6] { board.place_card(player, stone, Card::build(num, 2)); }
+        board.place_card(player.get_other(), stone, Card::build(1,5));
+        board.place_card(player.get_other(), stone, Card::build(3,5));
+
+        // Everything the other player could beat the color with is played elsewhere.
+        for (i, num) in [2, 6, 7, 8, 9].into_iter().enumerate() {
+            board.place_card(player, Stone::build(i as u8 / 3), Card::build(num, 5));
+        }
+
+        assert!(board.proto_is_legal_claim(player, stone));
+
+        // Without the advantage, the same tie loses.
+        let mut fields: Vec<String> = board.notation().split_whitespace().map(String::from).collect();
+        fields[3].replace_range(stone.get_index()..stone.get_index() + 1, "-");
+        let without_advantage = CardsOnBoard::from_notation(&fields.join(" ")).unwrap();
+
+        assert_eq!(without_advantage.advantage(stone), None);
+        assert!(!without_advantage.proto_is_legal_claim(player, stone));
+    }
+
+    // A claim is legal when every way the other player's cards can end up, with cards not yet played, loses.
+    // Equal formations go to whoever completed the stone first.
+    fn reference_is_legal_claim(board: &CardsOnBoard, player: Player, stone: Stone) -> bool {
+        let own = board.cards_of(player, stone);
+
+        if !own.is_full() { return false; }
+
+        let other = board.cards_of(player.get_other(), stone);
+        let unplayed: Vec<Card> = (1..=NUM_OF_NUMS)
+            .cartesian_product(1..=NUM_OF_COLORS)
+            .map(|(num, color)| Card::build(num, color))
+            .filter(|card| !board.is_present(card))
+            .collect();
+
+        let loses = |cards: &[Card]| {
+            let mut full = other.clone();
+            for card in cards { full.push(card.clone()); }
+
+            let (mine, theirs) = (own.strength().rank(), full.strength().rank());
+
+            mine > theirs || (mine == theirs && board.advantage(stone) == Some(player))
+        };
+
+        let n = unplayed.len();
+
+        match other.len() {
+            3 => loses(&[]),
+            2 => (0..n).all(|i| loses(&[unplayed[i].clone()])),
+            1 => (0..n).all(|i| (i + 1..n).all(|j| loses(&[unplayed[i].clone(), unplayed[j].clone()]))),
+            _ => (0..n).all(|i| (i + 1..n).all(|j| (j + 1..n).all(|k| {
+                loses(&[unplayed[i].clone(), unplayed[j].clone(), unplayed[k].clone()])
+            }))),
+        }
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let player = Player::build(0);
+        let stone = Stone::build(4);
+
+        for round in 0..400 {
+            let mut deck: Vec<Card> = (1..=NUM_OF_NUMS).cartesian_product(1..=NUM_OF_COLORS).map(|(n, c)| Card::build(n, c)).collect();
+            deck.shuffle(&mut rng);
+
+            let mut board = CardsOnBoard::new();
+            // Few positions with an empty stone for the other player, as the reference is slow on them.
+            let other_len = if round % 20 == 0 { 0 } else { 1 + round % 3 };
+
+            // Narrow the colors in play so that ties are common.
+            deck.sort_by_key(|card| card.color() > 2);
+
+            let (mine, theirs) = deck.split_at(3);
+            let other_first = other_len == 3 && rng.gen();
+
+            if other_first { theirs[..3].iter().for_each(|card| board.place_card(player.get_other(), stone, card.clone())); }
+            mine.iter().for_each(|card| board.place_card(player, stone, card.clone()));
+            if !other_first { theirs[..other_len].iter().for_each(|card| board.place_card(player.get_other(), stone, card.clone())); }
+
+            // Cards played elsewhere are no longer available to the other player.
+            let played = rng.gen_range(0..30);
+
+            for (i, card) in theirs[3..].iter().take(played).enumerate() {
+                // Two cards at most per player on each of the other stones.
+                let other_stone = (i / 2) % 8;
+                let other_stone = if other_stone < stone.get_index() { other_stone } else { other_stone + 1 };
+
+                board.place_card(Player::build((i % 2) as u8), Stone::build(other_stone as u8), card.clone());
+            }
+
+            assert_eq!(board.proto_is_legal_claim(player, stone), reference_is_legal_claim(&board, player, stone), "{}", board.notation());
+        }
+    }
+
     #[test]
     fn test_789_color_run() {
         let mut board = CardsOnBoard::new();
@@ -650,6 +793,7 @@ mod test_proto_legal_claim {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_689() {
         let player = Player::build(0);
         let stone = Stone::build(1);
@@ -762,6 +906,7 @@ mod test_terminal_state {
     // Tests for terminal_state assumes that implementation depends only on the claimed stones.
     // The tests currently access self.claims (self is an instance of CardsOnBoard).
 
+    #[allow(clippy::unnecessary_cast)]
     fn five_claimed_rec(claims: Rc<RefCell<Claims>>, player: Player, stone: usize, count: u8, others_streak: u8) {
         if stone == NUM_OF_STONES as usize {
             let mut board = CardsOnBoard::new();
@@ -821,6 +966,7 @@ mod test_terminal_state {
         );
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn three_streak_rec(
         claims: Rc<RefCell<Claims>>,
         player: Player,
@@ -958,6 +1104,7 @@ mod test_terminal_state {
         );
     }
 
+    #[allow(clippy::only_used_in_recursion)]
     fn not_terminal_rec(
         claims: Rc<RefCell<Claims>>,
         stone: usize,
@@ -1113,11 +1260,160 @@ mod test_display {
     }
 }
 
+#[cfg(test)]
+mod test_notation {
+    use super::*;
+
+    #[test]
+    fn test_empty_board() {
+        let board = CardsOnBoard::new();
+
+        assert_eq!(board.notation(), "//////// //////// --------- ---------");
+        assert_eq!(CardsOnBoard::from_notation(&board.notation()).unwrap().notation(), board.notation());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut board = CardsOnBoard::new();
+
+        board.place_card(Player::build(0), Stone::build(0), Card::build(7,1));
+        board.place_card(Player::build(0), Stone::build(0), Card::build(8,1));
+        board.place_card(Player::build(0), Stone::build(0), Card::build(9,1));
+        board.place_card(Player::build(1), Stone::build(4), Card::build(2,3));
+        board.place_card(Player::build(1), Stone::build(8), Card::build(5,6));
+        board.place_card(Player::build(1), Stone::build(8), Card::build(5,5));
+        board.place_card(Player::build(1), Stone::build(8), Card::build(5,4));
+        board.place_card(Player::build(0), Stone::build(8), Card::build(1,4));
+        board.place_card(Player::build(0), Stone::build(8), Card::build(1,5));
+        board.place_card(Player::build(0), Stone::build(8), Card::build(1,6));
+
+        assert!(board.claim(Player::build(0), Stone::build(0)));
+
+        let notation = board.notation();
+
+        assert_eq!(notation, "Pu7,Pu8,Pu9////////Ye1,Gr1,Bl1 ////Re2////Bl5,Gr5,Ye5 1-------- 1-------2");
+
+        let parsed = CardsOnBoard::from_notation(&notation).unwrap();
+
+        assert_eq!(parsed.notation(), notation);
+        assert_eq!(parsed.claims.who_claimed(Stone::build(0)), Some(Player::build(0)));
+        assert_eq!(parsed.advantage[8], Some(Player::build(1)));
+        assert!(parsed.present_cards.is_present(&Card::build(5,5)));
+        assert!(!parsed.present_cards.is_present(&Card::build(5,1)));
+    }
+
+    #[test]
+    fn test_bad_notation() {
+        let bad = [
+            "",
+            "//////// //////// ---------",
+            "/////// //////// --------- ---------",
+            "Pu1///////// //////// --------- ---------",
+            "Pu1,Pu2,Pu3,Pu4//////// //////// --------- ---------",
+            "Pu1//////// Pu1//////// --------- ---------",
+            "Xx1//////// //////// --------- ---------",
+            "//////// //////// -------- ---------",
+            "//////// //////// ---------- ---------",
+            "//////// //////// --------- ---3-----",
+        ];
+
+        for notation in bad {
+            assert!(CardsOnBoard::from_notation(notation).is_err(), "{notation}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_zobrist {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    // Fills a board with random cards and claims, checking the incremental hash after every change.
+    fn random_board(rng: &mut StdRng) -> CardsOnBoard {
+        let mut deck: Vec<Card> = (1..=NUM_OF_NUMS).cartesian_product(1..=NUM_OF_COLORS).map(|(num, color)| Card::build(num, color)).collect();
+        deck.shuffle(rng);
+
+        let mut board = CardsOnBoard::new();
+
+        for card in deck.into_iter().take(rng.gen_range(0..=40)) {
+            let player = Player::build(rng.gen_range(0..NUM_OF_PLAYERS));
+            let stones = board.available_stones_for(player);
+
+            if stones.is_empty() { continue; }
+
+            board.place_card(player, stones[rng.gen_range(0..stones.len())], card);
+            assert_eq!(board.zobrist(), board.compute_zobrist());
+
+            for stone in (0..NUM_OF_STONES).map(Stone::build) {
+                board.claim(player, stone);
+                assert_eq!(board.zobrist(), board.compute_zobrist());
+            }
+        }
+
+        board
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..50 {
+            let board = random_board(&mut rng);
+            let parsed = CardsOnBoard::from_notation(&board.notation()).unwrap();
+
+            assert_eq!(parsed.zobrist(), board.zobrist());
+        }
+
+        assert_eq!(CardsOnBoard::new().zobrist(), 0);
+    }
+
+    #[test]
+    fn test_order_independent() {
+        let mut board1 = CardsOnBoard::new();
+        let mut board2 = CardsOnBoard::new();
+
+        board1.place_card(Player::build(0), Stone::build(2), Card::build(4, 1));
+        board1.place_card(Player::build(1), Stone::build(5), Card::build(7, 3));
+        board2.place_card(Player::build(1), Stone::build(5), Card::build(7, 3));
+        board2.place_card(Player::build(0), Stone::build(2), Card::build(4, 1));
+
+        assert_eq!(board1.zobrist(), board2.zobrist());
+
+        // The same card elsewhere, or for the other player, is a different position.
+        let mut board3 = CardsOnBoard::new();
+        board3.place_card(Player::build(1), Stone::build(2), Card::build(4, 1));
+        board3.place_card(Player::build(1), Stone::build(5), Card::build(7, 3));
+
+        assert_ne!(board1.zobrist(), board3.zobrist());
+    }
+
+    #[test]
+    fn test_no_collisions() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut seen: HashMap<u64, String> = HashMap::new();
+
+        for _ in 0..2000 {
+            let board = random_board(&mut rng);
+            let notation = board.notation();
+
+            if let Some(other) = seen.insert(board.zobrist(), notation.clone()) {
+                assert_eq!(other, notation);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Hash, PartialEq, Eq)]
 pub struct CardsOnBoard {
     advantage: Advantage,
     cards: Vec<Vec<StoneCards>>, // Maybe use array\slices somehow.
     present_cards: PresentCards,
     claims: Claims,
+    // Zobrist hash of the cards, claims and advantage, kept up to date as the board changes.
+    zobrist: u64,
 }
 
 impl CardsOnBoard {
@@ -1134,9 +1430,10 @@ impl CardsOnBoard {
 
         CardsOnBoard {
             advantage: Advantage::new(),
-            cards: cards,
+            cards,
             present_cards: PresentCards::new(),
             claims: Claims::new(),
+            zobrist: 0,
         }
     }
 
@@ -1145,15 +1442,17 @@ impl CardsOnBoard {
 
         self.present_cards.add_card(&card);
 
+        self.zobrist ^= zobrist::card_key(player, stone, &card);
+
         stone_cards.push(card);
 
-        if stone_cards.is_full() {
-            if let None = self.advantage[stone.get_index()] {
-                self.advantage.set(player, stone);
-            }
+        if stone_cards.is_full() && self.advantage[stone.get_index()].is_none() {
+            self.advantage.set(player, stone);
+            self.zobrist ^= zobrist::advantage_key(player, stone);
         }
     }
 
+    #[allow(clippy::assertions_on_constants)]
     fn proto_is_legal_claim(&self, player: Player, stone: Stone) -> bool {
         let cards_of_player = &self.cards[player.get_index()][stone.get_index()];
 
@@ -1166,74 +1465,28 @@ impl CardsOnBoard {
         // Rest of the code assumes the following:
         assert!(STONE_CARDS_LIMIT == 3);
 
-        let all_nonpresent_cards = || {
-            (1..=NUM_OF_NUMS)
-            .cartesian_product(1..=NUM_OF_COLORS)
-            .map(|(num,color)| Card::build(num, color))
-            .filter(|card| !self.present_cards.is_present(card))
-        };
+        // Equal formations go to whoever completed the stone first. Against an incomplete stone,
+        // that is the player claiming it.
+        let has_advantage = self.advantage[stone.get_index()] == Some(player);
+        let beats = |other: Formation| cards_of_player_strength > other || (cards_of_player_strength == other && has_advantage);
 
         match cards_of_other.len() {
-            3 => {
-                if self.advantage[stone.get_index()].is_some() && self.advantage[stone.get_index()].unwrap() == player {
-                    cards_of_player_strength >= cards_of_other.strength()
-                } else {
-                    cards_of_player_strength > cards_of_other.strength()
-                }
-            }
-            2 => {
-                for card in all_nonpresent_cards() {
-                    let mut tmp = cards_of_other.clone();
-                    tmp.push(card);
-
-                    if tmp.strength() > cards_of_player_strength {
-                        return false;
-                    }
-                }
-
-                true
-            }
-            1 => {
-                for card1 in all_nonpresent_cards() {
-                    for card2 in all_nonpresent_cards().filter(|card| *card != card1) {
-                        let mut tmp = cards_of_other.clone();
-                        tmp.push(card1.clone());
-                        tmp.push(card2);
-
-                        if tmp.strength() > cards_of_player_strength {
-                            return false;
-                        }   
-                    }
-                }
-
-                true
-            }
-            0 => {
-                for card1 in all_nonpresent_cards() {
-                    for card2 in all_nonpresent_cards().filter(|card| *card != card1) {
-                        for card3 in all_nonpresent_cards().filter(|card| *card != card2 && *card != card1) {
-                            let mut tmp = StoneCards::new();
-                            tmp.push(card1.clone());
-                            tmp.push(card2.clone());
-                            tmp.push(card3);
-
-                            if tmp.strength() > cards_of_player_strength {
-                                return false;
-                            }   
-                        }
-                    }
-                }
-
-                true
-            }
+            3 => beats(cards_of_other.strength()),
+            // Every completion of the other player's cards with cards not yet played must be beaten.
+            0..=2 => !cards_of_other.any_completion(&self.unplayed_cards(), |full| !beats(full.strength())),
 
             _ => panic!("Unexcpected num of cards.")
         }
     }
 
+    pub fn is_legal_claim(&self, player: Player, stone: Stone) -> bool {
+        self.claims.who_claimed(stone).is_none() && self.proto_is_legal_claim(player, stone)
+    }
+
     pub fn claim(&mut self, player: Player, stone: Stone) -> bool{
-        if self.claims.who_claimed(stone).is_none() && self.proto_is_legal_claim(player, stone) {
+        if self.is_legal_claim(player, stone) {
             self.claims.claim(player, stone);
+            self.zobrist ^= zobrist::claim_key(player, stone);
 
             true
         } else {
@@ -1241,6 +1494,34 @@ impl CardsOnBoard {
         }
     }
 
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    // The Zobrist hash computed from scratch, which `zobrist` keeps up to date incrementally.
+    fn compute_zobrist(&self) -> u64 {
+        let mut hash = 0;
+
+        for player in (0..NUM_OF_PLAYERS).map(Player::build) {
+            for stone in (0..NUM_OF_STONES).map(Stone::build) {
+                for card in self.cards[player.get_index()][stone.get_index()].iter() {
+                    hash ^= zobrist::card_key(player, stone, card);
+                }
+
+                if self.claims.who_claimed(stone) == Some(player) {
+                    hash ^= zobrist::claim_key(player, stone);
+                }
+
+                if self.advantage[stone.get_index()] == Some(player) {
+                    hash ^= zobrist::advantage_key(player, stone);
+                }
+            }
+        }
+
+        hash
+    }
+
+    #[allow(clippy::assertions_on_constants)]
     pub fn terminal_state(&self) -> Option<Player> {
         // Current implementation assumes the following
         assert!(NUM_OF_STONES == 9);
@@ -1274,6 +1555,11 @@ impl CardsOnBoard {
         None
     }
 
+    // Stones `player` may legally claim right now.
+    pub fn claimable_stones(&self, player: Player) -> Vec<Stone> {
+        self.available_stones().into_iter().filter(|stone| self.proto_is_legal_claim(player, *stone)).collect()
+    }
+
     pub fn available_stones(&self) -> Vec<Stone> {
         self.claims
             .iter()
@@ -1292,13 +1578,187 @@ impl CardsOnBoard {
             .collect()
     }
 
+    pub fn cards_of(&self, player: Player, stone: Stone) -> &StoneCards {
+        &self.cards[player.get_index()][stone.get_index()]
+    }
+
+    pub fn who_claimed(&self, stone: Stone) -> Option<Player> {
+        self.claims.who_claimed(stone)
+    }
+
+    // The player who completed the stone first, if any.
+    pub fn advantage(&self, stone: Stone) -> Option<Player> {
+        self.advantage[stone.get_index()]
+    }
+
+    pub fn is_present(&self, card: &Card) -> bool {
+        self.present_cards.is_present(card)
+    }
+
+    // Cards not on the board, in a hand or in the deck.
+    pub fn unplayed_cards(&self) -> CardMask {
+        let mut unplayed = CardMask::all();
+
+        for card in CardMask::all().iter().filter(|card| self.present_cards.is_present(card)) {
+            unplayed.remove(&card);
+        }
+
+        unplayed
+    }
+
+    pub fn num_of_cards(&self) -> usize {
+        self.cards.iter().flatten().map(|cards| cards.len()).sum()
+    }
+
+    // The board with every card and stone renamed, e.g. by a symmetry of the game (see `crate::symmetry`).
+    // Cards on a stone are kept sorted, as their order does not matter.
+    pub fn relabeled<C: Fn(&Card) -> Card, S: Fn(Stone) -> Stone>(&self, card_map: C, stone_map: S) -> Self {
+        let mut board = CardsOnBoard::new();
+
+        for stone in (0..NUM_OF_STONES).map(Stone::build) {
+            let target = stone_map(stone);
+
+            for player in (0..NUM_OF_PLAYERS).map(Player::build) {
+                let mut cards: Vec<Card> = self.cards[player.get_index()][stone.get_index()].iter().map(&card_map).collect();
+                cards.sort_by_key(|card| (card.num(), card.color()));
+
+                for card in cards {
+                    board.present_cards.add_card(&card);
+                    board.cards[player.get_index()][target.get_index()].push(card);
+                }
+            }
+
+            if let Some(player) = self.claims.who_claimed(stone) {
+                board.claims.claim(player, target);
+            }
+
+            if let Some(player) = self.advantage[stone.get_index()] {
+                board.advantage.set(player, target);
+            }
+        }
+
+        board.zobrist = board.compute_zobrist();
+
+        board
+    }
+
     pub fn any_available_stones_for(&self, player: Player) -> bool {
         self.claims
             .iter()
             .enumerate()
-            .filter(|item| item.1.is_none() && !self.cards[player.get_index()][item.0].is_full())
-            .next()
-            .is_some()
+            .any(|item| item.1.is_none() && !self.cards[player.get_index()][item.0].is_full())
+    }
+}
+
+impl Default for CardsOnBoard {
+    fn default() -> Self { Self::new() }
+}
+
+impl CardsOnBoard {
+    // Text notation, used to communicate positions with external processes.
+    //
+    // A board is written as four whitespace separated fields:
+    // the cards of player 1, the cards of player 2, the claims and the advantages.
+    // Cards of a player are listed stone by stone, separated by '/', with the cards
+    // of a single stone separated by ','. Claims and advantages are one character per stone:
+    // '-' for none, or the number of the player ('1' or '2').
+    //
+    // e.g. "Pu1,Pu2,Pu3//////// Re5//////// 1-------- 1--------"
+
+    pub fn notation(&self) -> String {
+        let cards_notation = |player: usize| {
+            self.cards[player]
+                .iter()
+                .map(|cards| cards.iter().map(|card| card.to_string()).join(","))
+                .join("/")
+        };
+
+        let per_stone_notation = |players: &mut dyn Iterator<Item = &Option<Player>>| {
+            players
+                .map(|p| match p {
+                    Some(p) => char::from(b'0' + p.get_player()),
+                    None => '-',
+                })
+                .collect::<String>()
+        };
+
+        format!(
+            "{} {} {} {}",
+            cards_notation(0),
+            cards_notation(1),
+            per_stone_notation(&mut self.claims.iter()),
+            per_stone_notation(&mut (0..NUM_OF_STONES as usize).map(|i| &self.advantage[i])),
+        )
+    }
+
+    pub fn from_notation(notation: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = notation.split_whitespace().collect();
+
+        if fields.len() != 4 {
+            return Err(format!("Expected 4 fields in board notation, got {}", fields.len()));
+        }
+
+        let mut board = CardsOnBoard::new();
+
+        for (player_idx, field) in fields[..2].iter().enumerate() {
+            let player = Player::build(player_idx as u8);
+            let stones: Vec<&str> = field.split('/').collect();
+
+            if stones.len() != NUM_OF_STONES as usize {
+                return Err(format!("Expected {} stones for player {}, got {}", NUM_OF_STONES, player.get_player(), stones.len()));
+            }
+
+            for (stone_idx, stone_str) in stones.into_iter().enumerate() {
+                let stone = Stone::build(stone_idx as u8);
+
+                for card_str in stone_str.split(',').filter(|s| !s.is_empty()) {
+                    let card: Card = card_str.parse()?;
+
+                    if board.present_cards.is_present(&card) {
+                        return Err(format!("Card {card} appears more than once"));
+                    }
+                    if board.cards[player.get_index()][stone.get_index()].is_full() {
+                        return Err(format!("Too many cards on stone {}", stone.get_stone()));
+                    }
+
+                    board.place_card(player, stone, card);
+                }
+            }
+        }
+
+        let parse_per_stone = |field: &str| -> Result<Vec<Vec<Stone>>, String> {
+            let mut per_player: Vec<Vec<Stone>> = vec![Vec::new(); NUM_OF_PLAYERS as usize];
+
+            if field.chars().count() != NUM_OF_STONES as usize {
+                return Err(format!("Expected {} characters, got \"{}\"", NUM_OF_STONES, field));
+            }
+
+            for (stone_idx, c) in field.chars().enumerate() {
+                match c.to_digit(10) {
+                    _ if c == '-' => (),
+                    Some(p) if p >= 1 && p <= NUM_OF_PLAYERS as u32 => {
+                        per_player[p as usize - 1].push(Stone::build(stone_idx as u8));
+                    }
+                    _ => return Err(format!("Invalid character '{c}' in \"{field}\"")),
+                }
+            }
+
+            Ok(per_player)
+        };
+
+        board.claims = Claims::new();
+        for (player_idx, stones) in parse_per_stone(fields[2])?.iter().enumerate() {
+            board.claims.claims(Player::build(player_idx as u8), stones);
+        }
+
+        board.advantage = Advantage::new();
+        for (player_idx, stones) in parse_per_stone(fields[3])?.iter().enumerate() {
+            board.advantage.set_multi(Player::build(player_idx as u8), stones);
+        }
+
+        board.zobrist = board.compute_zobrist();
+
+        Ok(board)
     }
 }
 
@@ -1356,32 +1816,33 @@ impl fmt::Display for CardsOnBoard {
 
         // Player 2's claimed stones
         board_string.push_str(&self.string_stones_for(Some(Player::build(1))));
-        board_string.push_str("\n");
+        board_string.push('\n');
 
         // Player 2's cards
         for i in (0..STONE_CARDS_LIMIT as usize).rev() {
             board_string.push_str(&self.string_nth_card_for(Player::build(1), i));
-            board_string.push_str("\n");
+            board_string.push('\n');
         }
 
         // Unclaimed stones
         board_string.push_str(&self.string_stones_for(None));
-        board_string.push_str("\n");
+        board_string.push('\n');
 
         // Player 1's cards
         for i in 0..STONE_CARDS_LIMIT as usize {
             board_string.push_str(&self.string_nth_card_for(Player::build(0), i));
-            board_string.push_str("\n");
+            board_string.push('\n');
         }
 
         // Player 1's claimed stones
         board_string.push_str(&self.string_stones_for(Some(Player::build(0))));
-        board_string.push_str("\n");
+        board_string.push('\n');
 
         write!(f, "{}", board_string)
     }
 }
 
+#[derive(Clone, Hash, PartialEq, Eq)]
 struct PresentCards {
     cards: [[bool; NUM_OF_COLORS as usize]; NUM_OF_NUMS as usize],
 }
@@ -1391,11 +1852,11 @@ impl PresentCards {
     }
 
     fn add_card(&mut self, card: &Card) {
-        self.cards[card.num_index() as usize][card.color_index() as usize] = true;
+        self.cards[card.num_index()][card.color_index()] = true;
     }
 
     fn is_present(&self, card: &Card) -> bool {
-        self.cards[card.num_index() as usize][card.color_index() as usize] == true
+        self.cards[card.num_index()][card.color_index()]
     }
 }
 
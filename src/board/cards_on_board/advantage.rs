@@ -4,6 +4,7 @@ use crate::common::NUM_OF_STONES;
 use crate::components::{Player, Stone};
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
@@ -48,6 +49,7 @@ mod tests {
     }
 }
 
+#[derive(Clone, Hash, PartialEq, Eq)]
 pub struct Advantage {
     advantage: [Option<Player>; NUM_OF_STONES as usize],
 }
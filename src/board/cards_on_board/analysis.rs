@@ -0,0 +1,189 @@
+// Reading the board beyond formal claims. A stone can be decided in practice long before it can be claimed:
+// when the worst formation a player can still end up with beats the best the opponent can, nothing left to play
+// changes the outcome. Bots use this to stop spending cards on decided stones, and to pick cards to dump.
+//
+// Each side completes their cards with cards that may still reach them: for the player, their hand and
+// the unseen cards, for the opponent, the unseen cards. Both sides may count on the same unseen card,
+// which only makes the analysis more cautious.
+
+use super::stonecards::{Formation, StoneCards};
+use super::CardsOnBoard;
+use crate::common::NUM_OF_STONES;
+use crate::components::{Card, CardMask, Player, Stone};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(cards: &[Card]) -> CardMask {
+        cards.iter().collect()
+    }
+
+    #[test]
+    fn test_stone_status() {
+        let player = Player::build(0);
+        let other = player.get_other();
+        let mut board = CardsOnBoard::new();
+
+        // Won before completing: the hand holds the 9 for a color run, and any third card beats a sum of 7.
+        board.place_card(player, Stone::build(0), Card::build(7,1));
+        board.place_card(player, Stone::build(0), Card::build(8,1));
+        for (num, color) in [(1,2), (2,3), (4,4)] { board.place_card(other, Stone::build(0), Card::build(num, color)); }
+
+        // Lost: the opponent completed a 7-8-9 color run first, which can at best be tied.
+        for num in 7..=9 { board.place_card(other, Stone::build(1), Card::build(num, 5)); }
+
+        // Claimed.
+        for num in 4..=6 { board.place_card(player, Stone::build(2), Card::build(num, 6)); }
+        for (num, color) in [(1,3), (3,4), (5,5)] { board.place_card(other, Stone::build(2), Card::build(num, color)); }
+        assert!(board.claim(player, Stone::build(2)));
+
+        let hand = mask(&[Card::build(9,1)]);
+        let mut unseen = board.unplayed_cards();
+        unseen.remove(&Card::build(9,1));
+
+        let statuses = board.stone_statuses(player, &hand, &unseen);
+
+        assert_eq!(statuses[0], StoneStatus::Won);
+        assert_eq!(statuses[1], StoneStatus::Lost);
+        assert_eq!(statuses[2], StoneStatus::Won);
+        assert!(statuses[3..].iter().all(|status| *status == StoneStatus::Contested));
+
+        // The opponent reads the same stones the other way around.
+        let statuses = board.stone_statuses(other, &unseen, &hand);
+
+        assert_eq!(statuses[1], StoneStatus::Won);
+        assert_eq!(statuses[2], StoneStatus::Lost);
+    }
+
+    #[test]
+    fn test_dead_cards() {
+        // The opponent completed three 9s first on the only stone left to the player, which only a color run beats.
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        for color in 1..=3 { board.place_card(player.get_other(), Stone::build(0), Card::build(9, color)); }
+
+        // The player is full everywhere else. The 2 of the fourth color is among their cards,
+        // while the 2 and 3 of the fifth color are still to be drawn.
+        let hand = mask(&[Card::build(1,4), Card::build(1,5)]);
+        let kept = mask(&[Card::build(2,5), Card::build(3,5)]);
+
+        let elsewhere: Vec<Card> = board.unplayed_cards().iter().filter(|card| !hand.contains(card) && !kept.contains(card)).take(24).collect();
+
+        assert!(elsewhere.contains(&Card::build(2,4)));
+
+        for (i, card) in elsewhere.into_iter().enumerate() {
+            board.place_card(player, Stone::build(1 + i as u8 / 3), card);
+        }
+
+        let mut unseen = board.unplayed_cards();
+        hand.iter().for_each(|card| unseen.remove(&card));
+
+        let dead = board.dead_cards(player, &hand, &unseen);
+
+        assert!(dead.contains(&Card::build(1,4)));
+        assert!(!dead.contains(&Card::build(1,5)));
+        assert_eq!(board.stone_status(player, Stone::build(0), &hand, &unseen), StoneStatus::Contested);
+
+        // Placing a dead card loses the stone.
+        let mut after = board.clone();
+        after.place_card(player, Stone::build(0), Card::build(1,4));
+        unseen.remove(&Card::build(1,4));
+
+        assert_eq!(after.stone_status(player, Stone::build(0), &mask(&[Card::build(1,5)]), &unseen), StoneStatus::Lost);
+    }
+}
+
+// A stone as seen by one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoneStatus {
+    // Claimed by the player, or won whatever is played.
+    Won,
+    // Claimed by the opponent, or lost whatever is played.
+    Lost,
+    Contested,
+}
+
+fn beats(formation: Formation, other: Formation, wins_ties: bool) -> bool {
+    formation > other || (formation == other && wins_ties)
+}
+
+// Status from the ranges of formations both sides can end up with, and whether the player completed the stone first.
+// None for a side that cannot complete its cards.
+fn outlook(mine: Option<(Formation, Formation)>, theirs: Option<(Formation, Formation)>, first: Option<bool>) -> StoneStatus {
+    match (mine, theirs) {
+        (Some((my_worst, my_best)), Some((their_worst, their_best))) => {
+            if beats(my_worst, their_best, first == Some(true)) {
+                StoneStatus::Won
+            } else if beats(their_worst, my_best, first == Some(false)) {
+                StoneStatus::Lost
+            } else {
+                StoneStatus::Contested
+            }
+        }
+        (Some(_), None) => StoneStatus::Won,
+        (None, Some(_)) => StoneStatus::Lost,
+        (None, None) => StoneStatus::Contested,
+    }
+}
+
+impl CardsOnBoard {
+    // Status of `stone` for `player`, holding `hand` while `unseen` are in the deck or the opponent's hand.
+    pub fn stone_status(&self, player: Player, stone: Stone, hand: &CardMask, unseen: &CardMask) -> StoneStatus {
+        if let Some(owner) = self.who_claimed(stone) {
+            return if owner == player { StoneStatus::Won } else { StoneStatus::Lost };
+        }
+
+        let mine = self.cards_of(player, stone).reachable_strengths(&union(hand, unseen));
+        let theirs = self.cards_of(player.get_other(), stone).reachable_strengths(unseen);
+
+        outlook(mine, theirs, self.advantage(stone).map(|first| first == player))
+    }
+
+    // Status of every stone for `player`, by stone index.
+    pub fn stone_statuses(&self, player: Player, hand: &CardMask, unseen: &CardMask) -> Vec<StoneStatus> {
+        (0..NUM_OF_STONES).map(|stone| self.stone_status(player, Stone::build(stone), hand, unseen)).collect()
+    }
+
+    // Cards of `hand` and `unseen` that cannot help `player` on any contested stone: wherever they are placed,
+    // the stone is lost. They are best dumped, and never worth drawing.
+    // The opponent keeps counting on the card, so a card may be missed, but is never wrongly called dead.
+    pub fn dead_cards(&self, player: Player, hand: &CardMask, unseen: &CardMask) -> Vec<Card> {
+        let candidates = union(hand, unseen);
+
+        let open: Vec<(Stone, Option<(Formation, Formation)>)> = self
+            .available_stones_for(player)
+            .into_iter()
+            .filter(|stone| self.stone_status(player, *stone, hand, unseen) == StoneStatus::Contested)
+            .map(|stone| (stone, self.cards_of(player.get_other(), stone).reachable_strengths(unseen)))
+            .collect();
+
+        candidates
+            .iter()
+            .filter(|card| {
+                let mut rest = candidates;
+                rest.remove(card);
+
+                open.iter().all(|(stone, theirs)| {
+                    let mut cards: StoneCards = self.cards_of(player, *stone).clone();
+                    cards.push(card.clone());
+
+                    let first = match self.advantage(*stone) {
+                        Some(first) => Some(first == player),
+                        None if cards.is_full() => Some(true),
+                        None => None,
+                    };
+
+                    outlook(cards.reachable_strengths(&rest), *theirs, first) == StoneStatus::Lost
+                })
+            })
+            .collect()
+    }
+}
+
+fn union(first: &CardMask, second: &CardMask) -> CardMask {
+    let mut union = *first;
+    second.iter().for_each(|card| union.insert(&card));
+    union
+}
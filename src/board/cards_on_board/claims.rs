@@ -46,7 +46,7 @@ mod tests {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Claims {
     claims: [Option<Player>; NUM_OF_STONES as usize],
 }
@@ -56,12 +56,12 @@ impl Claims {
     }
 
     pub fn claim(&mut self, player: Player, stone: Stone) {
-        self.claims[stone.get_index() as usize] = Some(player);
+        self.claims[stone.get_index()] = Some(player);
     }
 
     pub fn claims(&mut self, player: Player, stones: &[Stone]) {
         for stone in stones {
-            self.claims[stone.get_index() as usize] = Some(player);
+            self.claims[stone.get_index()] = Some(player);
         }
     }
 
@@ -70,6 +70,7 @@ impl Claims {
     }
 
     // ONLY FOR TESTING!!!
+    #[cfg(test)]
     pub fn unclaim(&mut self, stone: Stone) {
         self.claims[stone.get_index()] = None;
     }
@@ -55,7 +55,7 @@ impl JobManager {
     fn add_jobs<T: IntoIterator<Item=StoneCards>>(&mut self, jobs: T) {
         let all_jobs: HashSet<StoneCards> = jobs.into_iter().collect();
 
-        for job in all_jobs.into_iter().map(|cards| Job {cards: cards}) {
+        for job in all_jobs.into_iter().map(|cards| Job {cards}) {
             let work_collector = WorkCollector::new(&job);
 
             let _ = self.workers.entry(job).or_insert(work_collector);
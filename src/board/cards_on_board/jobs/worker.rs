@@ -5,6 +5,7 @@ use std::ops::Index;
 use crate::common::{STONE_CARDS_LIMIT, NUM_OF_NUMS, NUM_OF_COLORS};
 use crate::components::Card;
 use super::{StoneCards, Job};
+use crate::board::cards_on_board::tables;
 
 use itertools::Itertools;
 
@@ -13,6 +14,7 @@ pub struct WorkCollector {
     data: Vec<Vec<Card>>,
 }
 impl WorkCollector {
+    #[allow(clippy::assertions_on_constants)]
     pub fn new(job: &Job) -> Self {
         // Current implementation assumes the following: 
         assert!(STONE_CARDS_LIMIT == 3);
@@ -56,13 +58,11 @@ impl Worker {
     
                 Worker::LessThanTwo(
                     BufReader::new(
-                        File::open(
-                            format!("duos_sorted_{}{}", card.num(), card.color())
-                        ).unwrap()
+                        File::open(tables::duos_file(card)).unwrap()
                     ).lines()
                 )
             }
-            0 => Worker::LessThanTwo(BufReader::new(File::open("triplets_sorted.txt").unwrap()).lines()),
+            0 => Worker::LessThanTwo(BufReader::new(File::open(tables::TRIPLETS_FILE).unwrap()).lines()),
             other => panic!("Unexpected len of cards: {}", other),
         }
     }
@@ -70,20 +70,8 @@ impl Worker {
     fn work(&mut self) -> Option<Vec<Card>> {
         match self {
             Worker::Full() => None,
-            Worker::Two(it) => {
-                if let Some(card) = it.next() {
-                    Some(vec![card])
-                } else {
-                    None
-                }
-            }
-            Worker::LessThanTwo(br) => {
-                if let Some(wrapped_line) = br.next() {
-                    Some(parse_line(wrapped_line))
-                } else {
-                    None
-                }
-            }
+            Worker::Two(it) => it.next().map(|card| vec![card]),
+            Worker::LessThanTwo(br) => br.next().map(parse_line),
 
         }
     }
@@ -108,16 +96,5 @@ fn two(cards: &StoneCards) -> <Vec<Card> as IntoIterator>::IntoIter {
 }
 
 fn parse_line(line: Result<String, Error>) -> Vec<Card> {
-    line
-        .unwrap()
-        .split("\n")
-        .map(|s| {
-            let num: u8 = s.parse().unwrap();
-
-            Card::build(num / 10, num & 10)
-        })
-        .collect::<Vec<Card>>()
+    tables::parse_line(&line.unwrap()).unwrap()
 }
-
-
-
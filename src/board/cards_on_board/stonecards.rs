@@ -1,14 +1,16 @@
+use std::cmp::Ordering;
+use std::fmt;
 use std::ops::Index;
 
-use crate::common::STONE_CARDS_LIMIT;
-use crate::components::Card;
+use itertools::Itertools;
+
+use crate::common::{NUM_OF_COLORS, NUM_OF_NUMS, STONE_CARDS_LIMIT};
+use crate::components::{Card, CardMask};
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use itertools::Itertools;
-
     #[test]
     fn test_is_full() {
         let mut cards = StoneCards::new();
@@ -21,6 +23,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::vec_init_then_push, clippy::clone_on_copy)]
     fn test_strength() {
         let sum_low = vec![Card::build(1,1), Card::build(1,2), Card::build(2,1)]; // 0
         let sum_high = vec![Card::build(9,1), Card::build(9,2), Card::build(8,2)]; // 1
@@ -85,6 +88,219 @@ mod tests {
             assert!(str1 >  str2, "{:?} - {str1} \n {:?} - {str2}", all_stuff[i1], all_stuff[i2]);
         }
     }
+
+    #[test]
+    fn test_formation() {
+        let formation = StoneCards { cards: vec![Card::build(9,4), Card::build(7,4), Card::build(8,4)] }.strength();
+
+        assert_eq!(formation, Formation::ColorRun { low: 7 });
+        assert_eq!(formation.to_string(), "Color run 7-8-9");
+        assert_eq!(formation.rank(), 72);
+
+        let three = StoneCards { cards: vec![Card::build(2,1), Card::build(2,3), Card::build(2,2)] }.strength();
+
+        assert_eq!(three.to_string(), "Three of a kind 2-2-2");
+        assert_eq!(three.value(), 2);
+        assert!(three < formation);
+
+        // Equal sums are equal formations, whatever the numbers.
+        let color1 = StoneCards { cards: vec![Card::build(6,2), Card::build(1,2), Card::build(2,2)] }.strength();
+        let color2 = StoneCards { cards: vec![Card::build(1,5), Card::build(3,5), Card::build(5,5)] }.strength();
+
+        assert_eq!(color1.to_string(), "Color 1-2-6");
+        assert_eq!(color1, color2);
+        assert!(color1 > Formation::Run { low: 7 });
+        assert!(color1 < three);
+        assert_eq!(Formation::Sum { nums: [2, 4, 9] }.to_string(), "Sum 2-4-9");
+        assert_eq!(Formation::Run { low: 3 }.to_string(), "Run 3-4-5");
+    }
+
+    fn stone_cards(cards: &[Card]) -> StoneCards {
+        StoneCards { cards: cards.to_vec() }
+    }
+
+    #[test]
+    fn test_category() {
+        assert_eq!(stone_cards(&[Card::build(1,1), Card::build(1,2), Card::build(2,1)]).category(), FormationCategory::Sum);
+        assert_eq!(stone_cards(&[Card::build(7,1), Card::build(8,1), Card::build(9,3)]).category(), FormationCategory::Run);
+        assert_eq!(stone_cards(&[Card::build(1,4), Card::build(2,4), Card::build(4,4)]).category(), FormationCategory::Color);
+        assert_eq!(stone_cards(&[Card::build(9,5), Card::build(9,6), Card::build(9,1)]).category(), FormationCategory::ThreeOfAKind);
+        assert_eq!(stone_cards(&[Card::build(9,4), Card::build(8,4), Card::build(7,4)]).category(), FormationCategory::ColorRun);
+    }
+
+    #[test]
+    fn test_completions() {
+        let cards = stone_cards(&[Card::build(7,1), Card::build(8,1)]);
+        // Cards already on the stone are not available again.
+        let available: CardMask = [Card::build(9,1), Card::build(1,2), Card::build(8,1)].iter().collect();
+
+        assert_eq!(cards.completions(&available).count(), 2);
+        assert_eq!(cards.reachable_strengths(&available), Some((Formation::Sum { nums: [1, 7, 8] }, Formation::ColorRun { low: 7 })));
+        assert_eq!(cards.reachable_strengths(&[Card::build(1,2)].iter().collect()), Some((Formation::Sum { nums: [1, 7, 8] }, Formation::Sum { nums: [1, 7, 8] })));
+        assert_eq!(cards.reachable_strengths(&CardMask::empty()), None);
+
+        let empty = StoneCards::new();
+
+        assert_eq!(empty.completions(&CardMask::all()).count(), 54 * 53 * 52 / 6);
+        assert_eq!(empty.max_reachable_strength(&CardMask::all()), Some(Formation::ColorRun { low: 7 }));
+        assert_eq!(empty.min_reachable_strength(&CardMask::all()), Some(Formation::Sum { nums: [1, 1, 2] }));
+
+        let full = stone_cards(&[Card::build(1,1), Card::build(1,2), Card::build(2,1)]);
+
+        assert_eq!(full.reachable_strengths(&CardMask::empty()), Some((Formation::Sum { nums: [1, 1, 2] }, Formation::Sum { nums: [1, 1, 2] })));
+
+        let mut count = 0;
+        assert!(!cards.any_completion(&CardMask::all(), |_| { count += 1; false }));
+        assert_eq!(count, cards.completions(&CardMask::all()).count());
+        assert!(cards.any_completion(&CardMask::all(), |full| full.strength() == Formation::ColorRun { low: 7 }));
+    }
+
+    #[test]
+    fn test_potential() {
+        let cards = stone_cards(&[Card::build(7,1), Card::build(8,1)]);
+
+        assert_eq!(cards.potential(&CardMask::all()), Some(FormationCategory::ColorRun));
+        assert_eq!(cards.potential(&[Card::build(9,2)].iter().collect()), Some(FormationCategory::Run));
+        assert_eq!(cards.potential(&[Card::build(2,1)].iter().collect()), Some(FormationCategory::Color));
+        assert_eq!(cards.potential(&[Card::build(2,2)].iter().collect()), Some(FormationCategory::Sum));
+        assert_eq!(cards.potential(&CardMask::empty()), None);
+
+        let pair = stone_cards(&[Card::build(3,1), Card::build(3,2)]);
+
+        assert_eq!(pair.potential(&[Card::build(3,6), Card::build(4,1)].iter().collect()), Some(FormationCategory::ThreeOfAKind));
+        assert_eq!(StoneCards::new().potential(&CardMask::all()), Some(FormationCategory::ColorRun));
+
+        // The potential is the best category among the completions.
+        let mut available = CardMask::all();
+        [Card::build(6,1), Card::build(9,1), Card::build(5,1)].iter().for_each(|card| available.remove(card));
+
+        for cards in [cards, pair, stone_cards(&[Card::build(2,3)]), StoneCards::new()] {
+            let best = cards.completions(&available).map(|full| full.category()).max();
+
+            assert_eq!(cards.potential(&available), best);
+        }
+    }
+}
+
+// A full formation, as compared to decide who wins a stone.
+// Formations of different categories compare by category, and formations of the same category by `value`,
+// so e.g. two colors with the same sum are equal whatever their numbers.
+#[derive(Debug, Clone, Copy)]
+pub enum Formation {
+    ColorRun { low: u8 },
+    ThreeOfAKind { num: u8 },
+    // Numbers in increasing order.
+    Color { nums: [u8; 3] },
+    Run { low: u8 },
+    // Numbers in increasing order.
+    Sum { nums: [u8; 3] },
+}
+impl Formation {
+    pub fn category(&self) -> FormationCategory {
+        match self {
+            Formation::ColorRun { .. } => FormationCategory::ColorRun,
+            Formation::ThreeOfAKind { .. } => FormationCategory::ThreeOfAKind,
+            Formation::Color { .. } => FormationCategory::Color,
+            Formation::Run { .. } => FormationCategory::Run,
+            Formation::Sum { .. } => FormationCategory::Sum,
+        }
+    }
+
+    // Breaks ties within a category: the lowest number of a run, the number of three of a kind,
+    // and the sum of the numbers otherwise.
+    pub fn value(&self) -> u8 {
+        match self {
+            Formation::ColorRun { low } | Formation::Run { low } => *low,
+            Formation::ThreeOfAKind { num } => *num,
+            Formation::Color { nums } | Formation::Sum { nums } => nums.iter().sum(),
+        }
+    }
+
+    // The formation on a single scale, for callers that need a number, e.g. to normalize.
+    //
+    // Type            | Rank
+    // ----------------|---------
+    // sum             | 4-26
+    // run             | 27-33
+    // color           | 40-56
+    // three of a kind | 57-65
+    // color run       | 66-72
+    pub fn rank(&self) -> u8 {
+        let offset = match self.category() {
+            FormationCategory::Sum => 0,
+            FormationCategory::Run => 26,
+            FormationCategory::Color => 33,
+            FormationCategory::ThreeOfAKind => 56,
+            FormationCategory::ColorRun => 65,
+        };
+
+        offset + self.value()
+    }
+
+    // The formation of three cards.
+    pub fn of(cards: [&Card; 3]) -> Self {
+        let mut nums = [cards[0].num(), cards[1].num(), cards[2].num()];
+        nums.sort_unstable();
+
+        let is_three_of_a_kind = nums[0] == nums[2];
+        let is_run = nums[0] + 1 == nums[1] && nums[1] + 1 == nums[2];
+        let is_color = cards[0].color() == cards[1].color() && cards[1].color() == cards[2].color();
+
+        if is_three_of_a_kind {
+            Formation::ThreeOfAKind { num: nums[0] }
+        } else if is_run && is_color {
+            Formation::ColorRun { low: nums[0] }
+        } else if is_run {
+            Formation::Run { low: nums[0] }
+        } else if is_color {
+            Formation::Color { nums }
+        } else {
+            Formation::Sum { nums }
+        }
+    }
+
+    // Numbers of the cards, in increasing order.
+    pub fn nums(&self) -> [u8; 3] {
+        match self {
+            Formation::ColorRun { low } | Formation::Run { low } => [*low, low + 1, low + 2],
+            Formation::ThreeOfAKind { num } => [*num; 3],
+            Formation::Color { nums } | Formation::Sum { nums } => *nums,
+        }
+    }
+}
+impl PartialEq for Formation {
+    fn eq(&self, other: &Self) -> bool { self.rank() == other.rank() }
+}
+impl Eq for Formation {}
+impl PartialOrd for Formation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl Ord for Formation {
+    fn cmp(&self, other: &Self) -> Ordering { self.rank().cmp(&other.rank()) }
+}
+impl fmt::Display for Formation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.category() {
+            FormationCategory::ColorRun => "Color run",
+            FormationCategory::ThreeOfAKind => "Three of a kind",
+            FormationCategory::Color => "Color",
+            FormationCategory::Run => "Run",
+            FormationCategory::Sum => "Sum",
+        };
+        let [low, mid, high] = self.nums();
+
+        write!(f, "{} {}-{}-{}", name, low, mid, high)
+    }
+}
+
+// Kinds of full formations, from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FormationCategory {
+    Sum,
+    Run,
+    Color,
+    ThreeOfAKind,
+    ColorRun,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -105,53 +321,125 @@ impl StoneCards {
         self.cards.push(card);
     }
 
-    pub fn strength(&self) -> u8 {
-        /*
-        Returns strength of card sequence, according to the following table:
-        
-        Type            | Value
-        ----------------|---------
-        sum             | 4-26
-        run             | 27-33
-        color           | 40-56
-        three of a kind | 57-65
-        color run       | 66-72
+    // The formation of a full set, panics otherwise.
+    pub fn strength(&self) -> Formation {
+        if !self.is_full() { panic!("Cannot determine strength of a non-full struct");}
 
-         */
+        Formation::of([&self.cards[0], &self.cards[1], &self.cards[2]])
+    }
 
-        if !self.is_full() { panic!("Cannot determine strength of a non-full struct");}
+    // Category of a full set.
+    pub fn category(&self) -> FormationCategory {
+        self.strength().category()
+    }
 
-        // assert!(STONE_CARDS_LIMIT == 3)
+    // Cards of `available` that could still join these, i.e. those not already here.
+    fn others(&self, available: &CardMask) -> CardMask {
+        let mut others = *available;
+        self.cards.iter().for_each(|card| others.remove(card));
+        others
+    }
 
-        // Check three of a kind 
-        if self.cards[0].num() == self.cards[1].num() && self.cards[1].num() == self.cards[2].num() {
-            return 56 + self.cards[0].num();
-        }
+    // Every full set these cards can become with cards of `available`. A full set is its only completion.
+    pub fn completions(&self, available: &CardMask) -> impl Iterator<Item = StoneCards> + '_ {
+        let others: Vec<Card> = self.others(available).iter().collect();
+
+        others
+            .into_iter()
+            .combinations(STONE_CARDS_LIMIT as usize - self.len())
+            .map(|completion| {
+                let mut full = self.clone();
+                completion.into_iter().for_each(|card| full.push(card));
+                full
+            })
+    }
 
-        let mut is_run = false;
-        let mut is_color = false;
+    // Whether some completion with cards of `available` satisfies `predicate`, stopping at the first one that does.
+    // Cheaper than going through `completions`, as the completions are built in place.
+    pub fn any_completion<F: FnMut(&StoneCards) -> bool>(&self, available: &CardMask, mut predicate: F) -> bool {
+        fn extend<F: FnMut(&StoneCards) -> bool>(cards: &mut StoneCards, others: &[Card], predicate: &mut F) -> bool {
+            if cards.is_full() { return predicate(cards); }
 
-        let mut cards_sorted = self.cards.clone();
-        cards_sorted.sort_unstable_by_key(|card| card.num());
+            for (i, card) in others.iter().enumerate() {
+                cards.cards.push(card.clone());
+                let found = extend(cards, &others[i + 1..], predicate);
+                cards.cards.pop();
 
-        // Check run
-        if cards_sorted[0].num() + 1 == cards_sorted[1].num() && cards_sorted[1].num() + 1 == cards_sorted[2].num() {
-            is_run = true;
-        }
+                if found { return true; }
+            }
 
-        // Check color
-        if self.cards[0].color() == self.cards[1].color() && self.cards[1].color() == self.cards[2].color() {
-            is_color = true;
+            false
         }
 
-        if is_run && is_color { 
-            65+cards_sorted[0].num() 
-        } else if is_run {
-            26+cards_sorted[0].num()
-        } else if is_color {
-            cards_sorted[0].num() + cards_sorted[1].num() + cards_sorted[2].num() + 33
+        let others: Vec<Card> = self.others(available).iter().collect();
+
+        extend(&mut self.clone(), &others, &mut predicate)
+    }
+
+    // Lowest and highest strengths of the completions, or None if `available` cannot complete these cards.
+    pub fn reachable_strengths(&self, available: &CardMask) -> Option<(Formation, Formation)> {
+        let mut extremes: Option<(Formation, Formation)> = None;
+
+        self.any_completion(available, |full| {
+            let strength = full.strength();
+
+            extremes = Some(extremes.map_or((strength, strength), |(min, max)| (min.min(strength), max.max(strength))));
+            false
+        });
+
+        extremes
+    }
+
+    pub fn max_reachable_strength(&self, available: &CardMask) -> Option<Formation> {
+        self.reachable_strengths(available).map(|(_, max)| max)
+    }
+
+    pub fn min_reachable_strength(&self, available: &CardMask) -> Option<Formation> {
+        self.reachable_strengths(available).map(|(min, _)| min)
+    }
+
+    // Best category these cards can still become with cards of `available`, or None if they cannot be completed.
+    // Unlike going through `completions`, this only checks the few cards each category needs.
+    pub fn potential(&self, available: &CardMask) -> Option<FormationCategory> {
+        let available = self.others(available);
+        let missing = STONE_CARDS_LIMIT as usize - self.len();
+
+        if available.len() < missing { return None; }
+
+        let colors: Vec<u8> = match self.cards.first() {
+            Some(card) if self.cards.iter().all(|c| c.color() == card.color()) => vec![card.color()],
+            Some(_) => Vec::new(),
+            None => (1..=NUM_OF_COLORS).collect(),
+        };
+        let nums: Vec<u8> = match self.cards.first() {
+            Some(card) if self.cards.iter().all(|c| c.num() == card.num()) => vec![card.num()],
+            Some(_) => Vec::new(),
+            None => (1..=NUM_OF_NUMS).collect(),
+        };
+
+        // Whether the cards fit a run from `low` to `low + 2`, with every missing number available in `fits`.
+        let run_from = |low: u8, fits: &dyn Fn(u8) -> bool| {
+            (low..=low + 2).all(|num| match self.cards.iter().filter(|c| c.num() == num).count() {
+                0 => (1..=NUM_OF_COLORS).any(|color| fits(color) && available.contains(&Card::build(num, color))),
+                1 => true,
+                _ => false,
+            }) && self.cards.iter().all(|c| (low..=low + 2).contains(&c.num()))
+        };
+        let run = |fits: &dyn Fn(u8) -> bool| (1..=NUM_OF_NUMS - 2).any(|low| run_from(low, fits));
+
+        let of_color = |color: u8| (1..=NUM_OF_NUMS).filter(|num| available.contains(&Card::build(*num, color))).count();
+        let of_num = |num: u8| (1..=NUM_OF_COLORS).filter(|color| available.contains(&Card::build(num, *color))).count();
+
+        if colors.iter().any(|color| run(&|c| c == *color)) {
+            Some(FormationCategory::ColorRun)
+        } else if nums.iter().any(|num| of_num(*num) >= missing) {
+            Some(FormationCategory::ThreeOfAKind)
+        } else if colors.iter().any(|color| of_color(*color) >= missing) {
+            Some(FormationCategory::Color)
+        } else if run(&|_| true) {
+            Some(FormationCategory::Run)
         } else {
-            cards_sorted[0].num() + cards_sorted[1].num() + cards_sorted[2].num()
+            Some(FormationCategory::Sum)
         }
     }
 
@@ -160,6 +448,11 @@ impl StoneCards {
     }
 
     pub fn len(&self) -> usize { self.cards.len() }
+
+    pub fn is_empty(&self) -> bool { self.cards.is_empty() }
+}
+impl Default for StoneCards {
+    fn default() -> Self { Self::new() }
 }
 
 impl Index<usize> for StoneCards {
@@ -0,0 +1,149 @@
+// Tables of completions sorted from strongest to weakest, read by the `jobs` module (see README):
+// resources/triplets_sorted.txt lists every set of three cards, and resources/duos_sorted_<num><color>
+// every pair of cards that completes the given card. Each line holds the cards of a completion
+// as <num><color>, separated by commas, e.g. "71,81,91" for 7, 8 and 9 of the first color.
+//
+// Completions are enumerated in card index order (see `encode::card_index`) and sorted by strength.
+// Equal colors are listed by their numbers. Other equal formations keep the order of enumeration.
+// `cargo run -- tables <output dir>` rebuilds the tables.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::stonecards::{Formation, StoneCards};
+use crate::common::{NUM_OF_COLORS, NUM_OF_NUMS};
+use crate::components::{Card, CardMask};
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_line("24,34").unwrap(), vec![Card::build(2,4), Card::build(3,4)]);
+        assert_eq!(parse_line("71,81,91").unwrap(), vec![Card::build(7,1), Card::build(8,1), Card::build(9,1)]);
+        assert_eq!(parse_line(&line(&[Card::build(9,6), Card::build(1,1)])).unwrap(), vec![Card::build(9,6), Card::build(1,1)]);
+
+        assert!(parse_line("").is_err());
+        assert!(parse_line("24,37").is_err());
+        assert!(parse_line("04").is_err());
+        assert!(parse_line("24;34").is_err());
+    }
+
+    #[test]
+    fn test_order() {
+        let triplets = triplets();
+
+        assert_eq!(triplets.len(), 54 * 53 * 52 / 6);
+        assert_eq!(line(&triplets[0]), "71,81,91");
+        assert!(triplets.windows(2).all(|pair| strength(&[], &pair[0]) >= strength(&[], &pair[1])));
+
+        let card = Card::build(1,1);
+        let duos = duos(&card);
+
+        assert_eq!(duos.len(), 53 * 52 / 2);
+        assert_eq!(line(&duos[0]), "21,31");
+        assert!(duos.iter().all(|duo| !duo.contains(&card)));
+    }
+
+    #[test]
+    fn test_shipped_tables() {
+        let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources");
+
+        let read = |name: &str| -> Vec<Vec<Card>> {
+            fs::read_to_string(resources.join(name))
+                .unwrap()
+                .lines()
+                .map(|l| parse_line(l).unwrap_or_else(|e| panic!("{name}: {e}")))
+                .collect()
+        };
+
+        assert!(read(TRIPLETS_FILE) == triplets(), "{TRIPLETS_FILE} is out of date");
+
+        for card in CardMask::all().iter() {
+            let name = duos_file(&card);
+
+            assert!(read(&name) == duos(&card), "{name} is out of date");
+        }
+    }
+}
+
+pub const TRIPLETS_FILE: &str = "triplets_sorted.txt";
+
+pub fn duos_file(card: &Card) -> String {
+    format!("duos_sorted_{}{}", card.num(), card.color())
+}
+
+// Every set of three cards, strongest first.
+pub fn triplets() -> Vec<Vec<Card>> {
+    sorted_completions(&[])
+}
+
+// Every pair of cards completing `card`, strongest completion first.
+pub fn duos(card: &Card) -> Vec<Vec<Card>> {
+    sorted_completions(std::slice::from_ref(card))
+}
+
+fn strength(cards: &[Card], completion: &[Card]) -> Formation {
+    let mut full = StoneCards::new();
+    cards.iter().chain(completion).for_each(|card| full.push(card.clone()));
+    full.strength()
+}
+
+// Cards completing `cards`, in the order of the tables.
+fn sorted_completions(cards: &[Card]) -> Vec<Vec<Card>> {
+    let mut stone_cards = StoneCards::new();
+    cards.iter().for_each(|card| stone_cards.push(card.clone()));
+
+    let mut completions: Vec<Vec<Card>> = stone_cards
+        .completions(&CardMask::all())
+        .map(|full| full.iter().skip(cards.len()).cloned().collect())
+        .collect();
+
+    // A stable sort, which keeps the order of enumeration between equal formations other than colors.
+    completions.sort_by_cached_key(|completion| {
+        let strength = strength(cards, completion);
+
+        match strength {
+            Formation::Color { nums } => (Reverse(strength), nums),
+            _ => (Reverse(strength), [0; 3]),
+        }
+    });
+
+    completions
+}
+
+// A line of a table.
+pub fn line(cards: &[Card]) -> String {
+    cards.iter().map(|card| format!("{}{}", card.num(), card.color())).collect::<Vec<String>>().join(",")
+}
+
+pub fn parse_line(line: &str) -> Result<Vec<Card>, String> {
+    line.split(',')
+        .map(|card| match card.trim().parse::<u8>() {
+            Ok(n) if (1..=NUM_OF_NUMS).contains(&(n / 10)) && (1..=NUM_OF_COLORS).contains(&(n % 10)) => Ok(Card::build(n / 10, n % 10)),
+            _ => Err(format!("Invalid card in table line: \"{line}\"")),
+        })
+        .collect()
+}
+
+// Writes every table to `dir`.
+pub fn write_tables(dir: &Path) -> io::Result<()> {
+    let write = |name: &str, table: Vec<Vec<Card>>| {
+        let lines: Vec<String> = table.iter().map(|cards| line(cards) + "\n").collect();
+
+        fs::write(dir.join(name), lines.concat())
+    };
+
+    fs::create_dir_all(dir)?;
+    write(TRIPLETS_FILE, triplets())?;
+
+    for card in CardMask::all().iter() {
+        write(&duos_file(&card), duos(&card))?;
+    }
+
+    Ok(())
+}
@@ -4,9 +4,10 @@ use crate::common::{CARDS_IN_DECK, NUM_OF_NUMS, NUM_OF_COLORS};
 use crate::components::Card;
 
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
     use itertools::Itertools;
@@ -33,6 +34,16 @@ mod tests {
         assert_ne!(Deck::new().deck, Deck::new().deck);
     }
 
+    #[test]
+    fn deck_with_rng_reproducible() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        assert_eq!(
+            Deck::with_rng(&mut StdRng::seed_from_u64(7)).deck,
+            Deck::with_rng(&mut StdRng::seed_from_u64(7)).deck
+        );
+    }
+
     #[test]
     fn deck_draw() {
         let mut deck = Deck::new();
@@ -44,11 +55,16 @@ mod tests {
     }
 }
 
+#[derive(Clone)]
 pub struct Deck {
     deck: Vec<Card>,
 }
 impl Deck {
     pub fn new() -> Self {
+        Self::with_rng(&mut thread_rng())
+    }
+
+    pub fn with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
         let mut deck: Vec<Card> = Vec::with_capacity(CARDS_IN_DECK as usize);
 
         for num in 1..=NUM_OF_NUMS {
@@ -57,15 +73,25 @@ impl Deck {
             }
         }
 
-        deck.shuffle(&mut thread_rng());
+        deck.shuffle(rng);
 
         Deck { deck }
     }
 
+    // Cards are drawn from the end of `cards`.
+    pub fn from_cards(cards: Vec<Card>) -> Self {
+        Deck { deck: cards }
+    }
+
     pub fn len(&self) -> usize { self.deck.len() }
 
+    pub fn is_empty(&self) -> bool { self.deck.is_empty() }
+
     pub fn draw(&mut self) -> Option<Card> { self.deck.pop() }
 }
+impl Default for Deck {
+    fn default() -> Self { Self::new() }
+}
 impl Index<usize> for Deck {
     type Output = Card; 
 
@@ -3,8 +3,10 @@ use std::fmt;
 
 use crate::components::Card;
 use crate::common::{CARDS_IN_HAND, SPACE};
+use crate::zobrist;
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
@@ -20,31 +22,81 @@ mod tests {
 
         assert_eq!(hand.to_string(), format!("Re1{SPACE}Pu5{SPACE}Bl8{SPACE}"));
     }
+
+    #[test]
+    fn test_zobrist() {
+        let mut hand1 = Hand::new();
+        let mut hand2 = Hand::new();
+
+        hand1.add(Card::build(1,3));
+        hand1.add(Card::build(5,1));
+        hand2.add(Card::build(5,1));
+        hand2.add(Card::build(2,2));
+        hand2.add(Card::build(1,3));
+
+        assert_ne!(hand1.zobrist(), hand2.zobrist());
+
+        hand2.remove(1);
+
+        assert_eq!(hand1.zobrist(), hand2.zobrist());
+
+        hand1.remove(0);
+        hand1.remove(0);
+
+        assert_eq!(hand1.zobrist(), 0);
+    }
 }
 
+#[derive(Clone)]
 pub struct Hand {
     hand: Vec<Card>,
+    // Zobrist hash of the cards, independent of their order.
+    zobrist: u64,
 }
 impl Hand {
     pub fn new() -> Self {
-        Hand { hand: Vec::with_capacity(CARDS_IN_HAND as usize) }
+        Hand { hand: Vec::with_capacity(CARDS_IN_HAND as usize), zobrist: 0 }
     }
 
     pub fn remove(&mut self, index: usize) -> Card {
-        self.hand.swap_remove(index)
+        let card = self.hand.swap_remove(index);
+
+        self.zobrist ^= zobrist::hand_key(&card);
+
+        card
     }
 
     pub fn add(&mut self, card: Card) {
-        if self.hand.len() == CARDS_IN_HAND as usize {
+        if self.is_full() {
             panic!("Hand is full!");
         }
 
+        self.zobrist ^= zobrist::hand_key(&card);
         self.hand.push(card);
     }
 
     pub fn len(&self) -> usize {
         self.hand.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.hand.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.hand.len() == CARDS_IN_HAND as usize
+    }
+
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Card> {
+        self.hand.iter()
+    }
+}
+impl Default for Hand {
+    fn default() -> Self { Self::new() }
 }
 impl Index<usize> for Hand {
     type Output = Card; 
@@ -7,5 +7,5 @@ pub const STONE_CARDS_LIMIT: u8 = 3;
 pub const CARDS_IN_DECK: u8 = NUM_OF_COLORS * NUM_OF_NUMS;
 
 // Constants for displaying
-pub const SPACE: &'static str = "   ";
-pub const STONE_STR: &'static str = "===";
\ No newline at end of file
+pub const SPACE: &str = "   ";
+pub const STONE_STR: &str = "===";
\ No newline at end of file
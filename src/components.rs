@@ -1,4 +1,5 @@
 use std::fmt;
+use std::str::FromStr;
 
 use crate::common::{NUM_OF_PLAYERS, NUM_OF_COLORS, NUM_OF_NUMS, NUM_OF_STONES};
 
@@ -54,6 +55,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn player_other() {
         assert!(NUM_OF_PLAYERS == 2);
         let p1 = Player::build(0);
@@ -80,6 +82,39 @@ mod tests {
         assert_eq!(Card::build(1,1).to_string(), "Pu1");
         assert_eq!(Card::build(9,4).to_string(), "Ye9");
     }
+
+    #[test]
+    fn card_from_str() {
+        for num in 1..=NUM_OF_NUMS {
+            for color in 1..=NUM_OF_COLORS {
+                let card = Card::build(num, color);
+
+                assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+            }
+        }
+
+        assert!("Pu0".parse::<Card>().is_err());
+        assert!("Pu10".parse::<Card>().is_err());
+        assert!("Xx1".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn card_mask() {
+        let mut mask: CardMask = [Card::build(9,6), Card::build(1,1)].iter().collect();
+
+        assert_eq!(mask.len(), 2);
+        assert!(mask.contains(&Card::build(9,6)));
+        assert!(!mask.contains(&Card::build(9,5)));
+        assert_eq!(mask.iter().collect::<Vec<Card>>(), vec![Card::build(1,1), Card::build(9,6)]);
+
+        mask.remove(&Card::build(1,1));
+        mask.remove(&Card::build(1,1));
+
+        assert_eq!(mask.len(), 1);
+        assert_eq!(CardMask::all().len(), (NUM_OF_NUMS * NUM_OF_COLORS) as usize);
+        assert!(CardMask::empty().is_empty());
+    }
 }
 
 #[derive(PartialEq, PartialOrd, Clone, Hash, Eq, Debug)]
@@ -89,7 +124,7 @@ pub struct Card {
 }
 impl Card {
     pub fn build(num: u8, color: u8) -> Self {
-        if num < 1 || num > NUM_OF_NUMS || color < 1 || color > NUM_OF_COLORS {
+        if !(1..=NUM_OF_NUMS).contains(&num) || !(1..=NUM_OF_COLORS).contains(&color) {
             panic!("Invalid number or color.\n
                 Expected num in range [1, NUM_OF_NUMS], got {}.\n
                 Expected color in range [1, NUM_OF_COLORS], got {}", num, color);
@@ -107,15 +142,87 @@ impl Card {
     pub fn color_index(&self) -> usize { (self.color-1) as usize }
 }
 
-const COLOR_TRANSLATIONS: [&'static str; NUM_OF_COLORS as usize] = ["Pu", "Br", "Re", "Ye", "Gr", "Bl"];
+const COLOR_TRANSLATIONS: [&str; NUM_OF_COLORS as usize] = ["Pu", "Br", "Re", "Ye", "Gr", "Bl"];
 
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}{}", COLOR_TRANSLATIONS[self.color_index()], self.num)
     }
 }
+impl FromStr for Card {
+    type Err = String;
+
+    /// Parses the notation produced by `Display`, e.g. "Pu1".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid card: {s}");
+
+        if !s.is_char_boundary(2) { return Err(invalid()); }
+
+        let (color_str, num_str) = s.split_at(2);
+
+        let color = COLOR_TRANSLATIONS
+            .iter()
+            .position(|c| *c == color_str)
+            .ok_or_else(invalid)? as u8 + 1;
+
+        match num_str.parse::<u8>() {
+            Ok(num) if (1..=NUM_OF_NUMS).contains(&num) => Ok(Card { num, color }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// A set of cards as one bit per card, e.g. the cards still available to complete a formation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct CardMask {
+    bits: u64,
+}
+impl CardMask {
+    pub fn empty() -> Self {
+        CardMask { bits: 0 }
+    }
+
+    pub fn all() -> Self {
+        CardMask { bits: (1 << (NUM_OF_NUMS * NUM_OF_COLORS)) - 1 }
+    }
+
+    fn bit(card: &Card) -> u64 {
+        1 << (card.num_index() * NUM_OF_COLORS as usize + card.color_index())
+    }
+
+    pub fn insert(&mut self, card: &Card) { self.bits |= Self::bit(card); }
+
+    pub fn remove(&mut self, card: &Card) { self.bits &= !Self::bit(card); }
+
+    pub fn contains(&self, card: &Card) -> bool { self.bits & Self::bit(card) != 0 }
+
+    pub fn len(&self) -> usize { self.bits.count_ones() as usize }
+
+    pub fn is_empty(&self) -> bool { self.bits == 0 }
+
+    // Cards of the set, by number and then color.
+    pub fn iter(&self) -> impl Iterator<Item = Card> {
+        let mut bits = self.bits;
+
+        std::iter::from_fn(move || {
+            if bits == 0 { return None; }
+
+            let index = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+
+            Some(Card::build(index / NUM_OF_COLORS + 1, index % NUM_OF_COLORS + 1))
+        })
+    }
+}
+impl<'a> FromIterator<&'a Card> for CardMask {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(cards: I) -> Self {
+        let mut mask = CardMask::empty();
+        cards.into_iter().for_each(|card| mask.insert(card));
+        mask
+    }
+}
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Player {
     p: u8,
 }
@@ -134,6 +241,7 @@ impl Player {
         self.p as usize
     }
 
+    #[allow(clippy::assertions_on_constants)]
     pub fn get_other(&self) -> Self {
         // Assumes the following:
         assert!(NUM_OF_PLAYERS == 2);
@@ -143,7 +251,7 @@ impl Player {
 
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Stone {
     s: u8,
 }
@@ -154,6 +262,7 @@ impl Stone {
         Stone {s}
     }
 
+    #[allow(clippy::result_unit_err)]
     pub fn try_build(s: u8) -> Result<Self, ()> {
         if s >= NUM_OF_STONES {
             Err(())
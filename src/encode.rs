@@ -0,0 +1,257 @@
+// Fixed-size encoding of an `Observation`, as input for machine learning models.
+//
+// All features are written from the point of view of the observing player ("own"),
+// the other player is the "opponent". Cards are indexed by `card_index`.
+//
+// Range (start offset)        | Length | Feature
+// ----------------------------|--------|---------------------------------------------------------
+// OWN_CARDS                   | 9 * 54 | One-hot of own cards on each stone (stone * 54 + card)
+// OPPONENT_CARDS              | 9 * 54 | One-hot of opponent cards on each stone
+// OWN_CLAIMS                  | 9      | 1 if the stone was claimed by the observing player
+// OPPONENT_CLAIMS             | 9      | 1 if the stone was claimed by the opponent
+// OWN_ADVANTAGE               | 9      | 1 if the observing player completed the stone first
+// OPPONENT_ADVANTAGE          | 9      | 1 if the opponent completed the stone first
+// HAND                        | 54     | One-hot of the cards in hand
+// UNSEEN                      | 54     | 1 for cards in the deck or in the opponent's hand
+// DECK_LEN                    | 1      | Cards left in the deck, divided by the initial deck size
+// OPPONENT_HAND_LEN           | 1      | Cards in the opponent's hand, divided by CARDS_IN_HAND
+//
+// Actions are indexed by hand slot and stone (see `action_index`), for policy targets.
+
+use crate::common::{CARDS_IN_DECK, CARDS_IN_HAND, NUM_OF_COLORS, NUM_OF_PLAYERS, NUM_OF_STONES};
+use crate::components::{Card, Stone};
+use crate::observation::Observation;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::NUM_OF_NUMS;
+    use crate::components::Player;
+    use crate::Game;
+
+    fn ones(encoding: &[f32], start: usize, len: usize) -> usize {
+        encoding[start..start+len].iter().filter(|x| **x == 1.0).count()
+    }
+
+    #[test]
+    fn test_card_index() {
+        let mut seen = [false; CARDS_IN_DECK as usize];
+
+        for num in 1..=NUM_OF_NUMS {
+            for color in 1..=NUM_OF_COLORS {
+                let card = Card::build(num, color);
+                let index = card_index(&card);
+
+                assert!(!seen[index]);
+                seen[index] = true;
+
+                assert_eq!(card_from_index(index), card);
+            }
+        }
+    }
+
+    #[test]
+    fn test_action_index() {
+        for hand_index in 0..CARDS_IN_HAND as usize {
+            for stone in (0..NUM_OF_STONES).map(Stone::build) {
+                let index = action_index(hand_index, stone);
+
+                assert!(index < NUM_OF_ACTIONS);
+                assert_eq!(action_from_index(index), (hand_index, stone));
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_game() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+        let encoding = encode(&game.observation(Player::build(0)));
+
+        assert_eq!(encoding.len(), ENCODING_LEN);
+        assert_eq!(ones(&encoding, OWN_CARDS, STONE_FEATURES), 0);
+        assert_eq!(ones(&encoding, OPPONENT_CARDS, STONE_FEATURES), 0);
+        assert_eq!(ones(&encoding, OWN_CLAIMS, 4 * STONES), 0);
+        assert_eq!(ones(&encoding, HAND, CARDS), 6);
+        assert_eq!(ones(&encoding, UNSEEN, CARDS), 48);
+        assert_eq!(encoding[DECK_LEN], 1.0);
+        assert_eq!(encoding[OPPONENT_HAND_LEN], 1.0);
+
+        for card in game.hand(Player::build(0)).iter() {
+            assert_eq!(encoding[HAND + card_index(card)], 1.0);
+            assert_eq!(encoding[UNSEEN + card_index(card)], 0.0);
+        }
+    }
+
+    #[test]
+    fn test_perspective() {
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(1));
+
+        let card1 = game.hand(Player::build(0))[0].clone();
+        game.apply_action((0, Stone::build(2)));
+
+        let card2 = game.hand(Player::build(1))[0].clone();
+        game.apply_action((0, Stone::build(7)));
+
+        let encoding1 = encode(&game.observation(Player::build(0)));
+        let encoding2 = encode(&game.observation(Player::build(1)));
+
+        let stone_card = |stone: usize, card: &Card| stone * CARDS_IN_DECK as usize + card_index(card);
+
+        assert_eq!(encoding1[OWN_CARDS + stone_card(2, &card1)], 1.0);
+        assert_eq!(encoding1[OPPONENT_CARDS + stone_card(7, &card2)], 1.0);
+        assert_eq!(encoding2[OWN_CARDS + stone_card(7, &card2)], 1.0);
+        assert_eq!(encoding2[OPPONENT_CARDS + stone_card(2, &card1)], 1.0);
+
+        assert_eq!(ones(&encoding1, OWN_CARDS, 2 * STONE_FEATURES), 2);
+        assert_eq!(encoding1[UNSEEN + card_index(&card1)], 0.0);
+        assert_eq!(encoding1[DECK_LEN], 40.0 / 42.0);
+    }
+
+    #[test]
+    fn test_legal_action_mask() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(2));
+        let mask = legal_action_mask(&game.observation(game.to_move()));
+
+        assert_eq!(mask.len(), NUM_OF_ACTIONS);
+
+        for (hand_index, stone) in game.legal_actions() {
+            assert_eq!(mask[action_index(hand_index, stone)], 1.0);
+        }
+        assert_eq!(mask.iter().filter(|x| **x == 1.0).count(), game.legal_actions().len());
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_encode_batch() {
+        let game = Game::new();
+        let observations = [game.observation(Player::build(0)), game.observation(Player::build(1))];
+
+        let batch = encode_batch(&observations);
+
+        assert_eq!(batch.shape(), &[2, ENCODING_LEN]);
+        assert_eq!(batch.row(0).to_vec(), encode(&observations[0]));
+        assert_eq!(batch.row(1), encode_array(&observations[1]));
+    }
+}
+
+const CARDS: usize = CARDS_IN_DECK as usize;
+const STONES: usize = NUM_OF_STONES as usize;
+const STONE_FEATURES: usize = STONES * CARDS;
+
+pub const OWN_CARDS: usize = 0;
+pub const OPPONENT_CARDS: usize = OWN_CARDS + STONE_FEATURES;
+pub const OWN_CLAIMS: usize = OPPONENT_CARDS + STONE_FEATURES;
+pub const OPPONENT_CLAIMS: usize = OWN_CLAIMS + STONES;
+pub const OWN_ADVANTAGE: usize = OPPONENT_CLAIMS + STONES;
+pub const OPPONENT_ADVANTAGE: usize = OWN_ADVANTAGE + STONES;
+pub const HAND: usize = OPPONENT_ADVANTAGE + STONES;
+pub const UNSEEN: usize = HAND + CARDS;
+pub const DECK_LEN: usize = UNSEEN + CARDS;
+pub const OPPONENT_HAND_LEN: usize = DECK_LEN + 1;
+pub const ENCODING_LEN: usize = OPPONENT_HAND_LEN + 1;
+
+pub const NUM_OF_ACTIONS: usize = CARDS_IN_HAND as usize * STONES;
+
+pub fn card_index(card: &Card) -> usize {
+    card.num_index() * NUM_OF_COLORS as usize + card.color_index()
+}
+
+pub fn card_from_index(index: usize) -> Card {
+    Card::build((index / NUM_OF_COLORS as usize) as u8 + 1, (index % NUM_OF_COLORS as usize) as u8 + 1)
+}
+
+pub fn action_index(hand_index: usize, stone: Stone) -> usize {
+    hand_index * STONES + stone.get_index()
+}
+
+pub fn action_from_index(index: usize) -> (usize, Stone) {
+    (index / STONES, Stone::build((index % STONES) as u8))
+}
+
+pub fn encode(observation: &Observation) -> Vec<f32> {
+    let mut encoding = vec![0.0; ENCODING_LEN];
+
+    encode_into(observation, &mut encoding);
+
+    encoding
+}
+
+// Writes the encoding into `encoding`, which must be of length ENCODING_LEN.
+pub fn encode_into(observation: &Observation, encoding: &mut [f32]) {
+    assert_eq!(encoding.len(), ENCODING_LEN, "Encoding buffer has the wrong length");
+
+    encoding.fill(0.0);
+
+    let board = observation.board;
+    let own = observation.player;
+    let opponent = own.get_other();
+
+    for stone in (0..NUM_OF_STONES).map(Stone::build) {
+        let s = stone.get_index();
+
+        for (player, offset) in [(own, OWN_CARDS), (opponent, OPPONENT_CARDS)] {
+            for card in board.cards_of(player, stone).iter() {
+                encoding[offset + s * CARDS + card_index(card)] = 1.0;
+            }
+        }
+
+        match board.who_claimed(stone) {
+            Some(p) if p == own => encoding[OWN_CLAIMS + s] = 1.0,
+            Some(_) => encoding[OPPONENT_CLAIMS + s] = 1.0,
+            None => (),
+        }
+
+        match board.advantage(stone) {
+            Some(p) if p == own => encoding[OWN_ADVANTAGE + s] = 1.0,
+            Some(_) => encoding[OPPONENT_ADVANTAGE + s] = 1.0,
+            None => (),
+        }
+    }
+
+    for card in observation.hand.iter() {
+        encoding[HAND + card_index(card)] = 1.0;
+    }
+
+    for card in observation.unseen_cards() {
+        encoding[UNSEEN + card_index(&card)] = 1.0;
+    }
+
+    let initial_deck_len = (CARDS_IN_DECK - NUM_OF_PLAYERS * CARDS_IN_HAND) as f32;
+
+    encoding[DECK_LEN] = observation.deck_len() as f32 / initial_deck_len;
+    encoding[OPPONENT_HAND_LEN] = observation.opponent_hand_len() as f32 / CARDS_IN_HAND as f32;
+}
+
+// 1 for every legal action of the observing player, indexed by `action_index`.
+pub fn legal_action_mask(observation: &Observation) -> Vec<f32> {
+    let mut mask = vec![0.0; NUM_OF_ACTIONS];
+
+    for stone in observation.board.available_stones_for(observation.player) {
+        for hand_index in 0..observation.hand.len() {
+            mask[action_index(hand_index, stone)] = 1.0;
+        }
+    }
+
+    mask
+}
+
+#[cfg(feature = "ndarray")]
+pub fn encode_array(observation: &Observation) -> ndarray::Array1<f32> {
+    ndarray::Array1::from(encode(observation))
+}
+
+// Encodes a batch of observations, one row per observation.
+#[cfg(feature = "ndarray")]
+pub fn encode_batch(observations: &[Observation]) -> ndarray::Array2<f32> {
+    let mut batch = ndarray::Array2::zeros((observations.len(), ENCODING_LEN));
+
+    for (mut row, observation) in batch.rows_mut().into_iter().zip(observations) {
+        encode_into(observation, row.as_slice_mut().expect("Rows of a standard layout array are contiguous."));
+    }
+
+    batch
+}
@@ -0,0 +1,367 @@
+// Reproducible comparisons between ways of searching with hidden information.
+//
+// Every pair of contenders plays the same deals (one per seed) twice, swapping seats,
+// so that neither side benefits from a lucky deal. Search randomness is seeded from the deal as well,
+// so an experiment is reproducible up to the timing columns.
+//
+// Two CSV tables come out of an experiment: a summary line per match (`summary_csv`),
+// and a line per decision with the root statistics of the search (`moves_csv`).
+
+use std::fmt::Write as _;
+use std::time::Instant;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::components::Player;
+use crate::search::opponent_model::{BayesianModel, FrequencyModel, OpponentModel};
+use crate::search::uct::{self, UctConfig};
+use crate::search::{begin_turn, ismcts, SearchResult};
+use crate::player::uct_player::{UctPlayer, UctSearch};
+use crate::player::Player as PlayerTrait;
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ExperimentConfig {
+        ExperimentConfig { deals: 2, seed: 5 }
+    }
+
+    fn contenders() -> Vec<Contender> {
+        standard_contenders(&UctConfig { iterations: 16, determinizations: 2, ..UctConfig::default() })
+    }
+
+    #[test]
+    fn test_run_match() {
+        let contenders = contenders();
+        let report = run_match(&contenders[1], &contenders[2], &config());
+
+        assert_eq!(report.wins.iter().sum::<usize>(), 4);
+        assert!(!report.moves.is_empty());
+
+        for (i, name) in [&contenders[1].name, &contenders[2].name].into_iter().enumerate() {
+            let moves = report.moves.iter().filter(|m| m.contender == *name).count();
+
+            assert!(moves > 0);
+            assert!((report.mean_millis(i) >= 0.0));
+        }
+
+        // Both seatings of a deal are played.
+        assert_eq!(report.moves.iter().filter(|m| m.ply == 0).count(), 4);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let contenders = contenders();
+
+        let report1 = run_match(&contenders[0], &contenders[3], &config());
+        let report2 = run_match(&contenders[0], &contenders[3], &config());
+
+        assert_eq!(report1.wins, report2.wins);
+        assert_eq!(report1.moves.len(), report2.moves.len());
+        assert!(report1.moves.iter().zip(report2.moves.iter()).all(|(m1, m2)| m1.action == m2.action));
+    }
+
+    #[test]
+    fn test_csv() {
+        let contenders = contenders();
+        let reports = run_experiment(&contenders[..2], &ExperimentConfig { deals: 1, seed: 0 });
+
+        assert_eq!(reports.len(), 1);
+
+        let summary = summary_csv(&reports);
+        let lines: Vec<&str> = summary.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+        assert!(lines[1].starts_with("perfect information,determinization,2,"));
+
+        let moves = moves_csv(&reports);
+
+        assert_eq!(moves.lines().count(), reports[0].moves.len() + 1);
+        assert!(moves.lines().all(|line| line.split(',').count() == moves.lines().next().unwrap().split(',').count()));
+    }
+}
+
+// How a contender handles the hidden cards.
+#[derive(Clone, Debug)]
+pub enum Method {
+    // Searches the real game, seeing the opponent's hand and the deck. An upper bound, not a fair player.
+    PerfectInformation,
+    // Determinized UCT with uniformly sampled hidden cards.
+    Determinization,
+    // Single observer information set MCTS.
+    Ismcts,
+    // Determinized UCT with hands sampled from an opponent model.
+    OpponentModel(ModelKind),
+}
+
+#[derive(Clone, Debug)]
+pub enum ModelKind {
+    Bayesian,
+    Frequency,
+}
+impl ModelKind {
+    fn build(&self) -> Box<dyn OpponentModel> {
+        match self {
+            ModelKind::Bayesian => Box::new(BayesianModel::default()),
+            ModelKind::Frequency => Box::new(FrequencyModel::default()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Contender {
+    pub name: String,
+    pub method: Method,
+    pub config: UctConfig,
+}
+impl Contender {
+    pub fn new(name: &str, method: Method, config: UctConfig) -> Self {
+        Contender { name: String::from(name), method, config }
+    }
+}
+
+// The four methods compared by the README, with the same search budget.
+pub fn standard_contenders(config: &UctConfig) -> Vec<Contender> {
+    vec![
+        Contender::new("perfect information", Method::PerfectInformation, config.clone()),
+        Contender::new("determinization", Method::Determinization, config.clone()),
+        Contender::new("ismcts", Method::Ismcts, config.clone()),
+        Contender::new("opponent model", Method::OpponentModel(ModelKind::Bayesian), config.clone()),
+    ]
+}
+
+#[derive(Clone, Debug)]
+pub struct ExperimentConfig {
+    // Number of deals per match, each played once from each seat.
+    pub deals: usize,
+    // Deal i is dealt with the seed `seed + i`.
+    pub seed: u64,
+}
+impl Default for ExperimentConfig {
+    fn default() -> Self {
+        ExperimentConfig { deals: 50, seed: 0 }
+    }
+}
+
+// Root statistics of a single decision.
+#[derive(Clone, Debug)]
+pub struct MoveLog {
+    pub contender: String,
+    pub deal: usize,
+    // Whether the first contender of the match sat as player 2.
+    pub swapped: bool,
+    pub ply: usize,
+    pub player: Player,
+    // The chosen card and stone.
+    pub action: String,
+    pub children: usize,
+    pub visits: u32,
+    pub best_visits: u32,
+    pub best_value: f64,
+    pub millis: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchReport {
+    pub contenders: [String; 2],
+    pub wins: [usize; 2],
+    pub moves: Vec<MoveLog>,
+}
+impl MatchReport {
+    pub fn games(&self) -> usize {
+        self.wins.iter().sum()
+    }
+
+    // Mean thinking time per decision of the contender at `index`.
+    pub fn mean_millis(&self, index: usize) -> f64 {
+        let times: Vec<f64> = self
+            .moves
+            .iter()
+            .filter(|m| m.contender == self.contenders[index])
+            .map(|m| m.millis)
+            .collect();
+
+        if times.is_empty() { 0.0 } else { times.iter().sum::<f64>() / times.len() as f64 }
+    }
+}
+
+// A contender during a single game.
+struct Searcher {
+    contender: Contender,
+    rng: StdRng,
+    // Keeps the opponent model up to date across the game.
+    modeling: Option<UctPlayer>,
+}
+impl Searcher {
+    fn new(contender: &Contender, seed: u64) -> Self {
+        let modeling = match &contender.method {
+            Method::OpponentModel(kind) => {
+                Some(UctPlayer::with_seed(UctSearch::new(contender.config.clone()).with_opponent_model(kind.build()), seed))
+            }
+            _ => None,
+        };
+
+        Searcher { contender: contender.clone(), rng: StdRng::seed_from_u64(seed), modeling }
+    }
+
+    fn search(&mut self, game: &Game) -> SearchResult {
+        let player = game.to_move();
+        let observation = game.observation(player);
+        let config = &self.contender.config;
+
+        match &self.contender.method {
+            Method::PerfectInformation => uct::search_perfect_information(game, config, &mut self.rng),
+            Method::Determinization => uct::search(&observation, config, &mut self.rng),
+            Method::Ismcts => ismcts::search(&observation, config, &mut self.rng),
+            Method::OpponentModel(_) => {
+                let bot = self.modeling.as_ref().expect("Opponent modeling contenders keep a player.");
+
+                bot.choose_action(game.hand(player), game.board(), player);
+                bot.last_search().expect("The player just searched.")
+            }
+        }
+    }
+}
+
+// Plays a deal to the end, and returns the winner.
+fn play_deal(searchers: &mut [Searcher; 2], deal: usize, seed: u64, swapped: bool, moves: &mut Vec<MoveLog>) -> Player {
+    let mut game = Game::with_rng(&mut StdRng::seed_from_u64(seed));
+    let mut ply = 0;
+
+    loop {
+        if let Some(winner) = begin_turn(&mut game) {
+            return winner;
+        }
+
+        if game.legal_actions().is_empty() {
+            game.pass();
+            continue;
+        }
+
+        let player = game.to_move();
+        let searcher = &mut searchers[player.get_index()];
+
+        let start = Instant::now();
+        let result = searcher.search(&game);
+        let millis = start.elapsed().as_secs_f64() * 1000.0;
+
+        let best = result.best();
+        let action = result.best_action(game.hand(player)).expect("A player with a legal action should not pass.");
+
+        moves.push(MoveLog {
+            contender: searcher.contender.name.clone(),
+            deal,
+            swapped,
+            ply,
+            player,
+            action: format!("{} {}", game.hand(player)[action.0], action.1.get_stone()),
+            children: result.children.len(),
+            visits: result.children.iter().map(|child| child.visits).sum(),
+            best_visits: best.visits,
+            best_value: best.mean_value(),
+            millis,
+        });
+
+        game.apply_action(action);
+        ply += 1;
+    }
+}
+
+pub fn run_match(first: &Contender, second: &Contender, config: &ExperimentConfig) -> MatchReport {
+    let mut report = MatchReport {
+        contenders: [first.name.clone(), second.name.clone()],
+        wins: [0, 0],
+        moves: Vec::new(),
+    };
+
+    for deal in 0..config.deals {
+        let seed = config.seed.wrapping_add(deal as u64);
+
+        for swapped in [false, true] {
+            // Each seat's search is seeded by the deal and the seat, whoever sits there.
+            let search_seed = |seat: u64| seed.wrapping_mul(2).wrapping_add(seat);
+
+            let mut searchers = if swapped {
+                [Searcher::new(second, search_seed(0)), Searcher::new(first, search_seed(1))]
+            } else {
+                [Searcher::new(first, search_seed(0)), Searcher::new(second, search_seed(1))]
+            };
+
+            let winner = play_deal(&mut searchers, deal, seed, swapped, &mut report.moves);
+
+            report.wins[usize::from(swapped) ^ winner.get_index()] += 1;
+        }
+    }
+
+    report
+}
+
+// Plays a match between every pair of contenders.
+pub fn run_experiment(contenders: &[Contender], config: &ExperimentConfig) -> Vec<MatchReport> {
+    let mut reports = Vec::new();
+
+    for (i, first) in contenders.iter().enumerate() {
+        for second in contenders[i + 1..].iter() {
+            reports.push(run_match(first, second, config));
+        }
+    }
+
+    reports
+}
+
+pub fn summary_csv(reports: &[MatchReport]) -> String {
+    let mut csv = String::from("first,second,games,first_wins,second_wins,first_win_rate,first_ms_per_move,second_ms_per_move\n");
+
+    for report in reports {
+        let games = report.games();
+        let win_rate = if games == 0 { 0.0 } else { report.wins[0] as f64 / games as f64 };
+
+        writeln!(
+            csv,
+            "{},{},{},{},{},{:.3},{:.3},{:.3}",
+            report.contenders[0],
+            report.contenders[1],
+            games,
+            report.wins[0],
+            report.wins[1],
+            win_rate,
+            report.mean_millis(0),
+            report.mean_millis(1),
+        )
+        .expect("Writing to a String cannot fail.");
+    }
+
+    csv
+}
+
+pub fn moves_csv(reports: &[MatchReport]) -> String {
+    let mut csv = String::from("first,second,contender,deal,swapped,ply,player,action,children,visits,best_visits,best_value,ms\n");
+
+    for report in reports {
+        for m in report.moves.iter() {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{:.4},{:.3}",
+                report.contenders[0],
+                report.contenders[1],
+                m.contender,
+                m.deal,
+                m.swapped,
+                m.ply,
+                m.player.get_player(),
+                m.action,
+                m.children,
+                m.visits,
+                m.best_visits,
+                m.best_value,
+                m.millis,
+            )
+            .expect("Writing to a String cannot fail.");
+        }
+    }
+
+    csv
+}
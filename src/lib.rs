@@ -1,26 +1,288 @@
 pub mod board;
 pub mod common;
 pub mod components;
+pub mod encode;
+pub mod experiment;
+pub mod observation;
+pub mod odds;
+pub mod perft;
 pub mod player;
+pub mod protocol;
+pub mod search;
+pub mod selfplay;
+pub mod symmetry;
+pub mod time_control;
+pub mod zobrist;
+#[cfg(feature = "python")]
+pub mod python;
 
 use std::fmt;
+use std::time::Instant;
 
 use board::cards_on_board::CardsOnBoard;
 use board::deck::Deck;
 use board::hand::Hand;
-use common::CARDS_IN_HAND;
-use components::Player;
+use common::{CARDS_IN_HAND, NUM_OF_PLAYERS};
+use components::{Player, Stone};
+use observation::Observation;
 use player::Player as PlayerTrait;
+use time_control::{Clock, TimeControl, TimeLeft};
 
+use rand::{thread_rng, Rng};
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+    use rand::{rngs::StdRng, SeedableRng};
+    use common::{CARDS_IN_DECK, NUM_OF_STONES};
+
+    #[test]
+    fn game_with_rng_reproducible() {
+        let game1 = Game::with_rng(&mut StdRng::seed_from_u64(3));
+        let game2 = Game::with_rng(&mut StdRng::seed_from_u64(3));
+
+        assert_eq!(game1.to_string(), game2.to_string());
+        assert_eq!(game1.deck_len(), (CARDS_IN_DECK - 2*CARDS_IN_HAND) as usize);
+    }
+
+    #[test]
+    fn game_apply_action() {
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(5));
+        let player = game.to_move();
+
+        assert_eq!(player, Player::build(0));
+        assert_eq!(game.legal_actions().len(), (CARDS_IN_HAND * NUM_OF_STONES) as usize);
+
+        let card = game.hand(player)[2].clone();
+
+        game.apply_action((2, Stone::build(4)));
+
+        assert_eq!(game.to_move(), Player::build(1));
+        assert_eq!(*game.board().cards_of(player, Stone::build(4)).iter().last().unwrap(), card);
+        assert_eq!(game.hand(player).len(), CARDS_IN_HAND as usize);
+        assert!(!game.hand(player).iter().any(|c| *c == card));
+        assert_eq!(game.deck_len(), (CARDS_IN_DECK - 2*CARDS_IN_HAND) as usize - 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn game_apply_illegal_action() {
+        let mut game = Game::new();
+
+        game.apply_action((CARDS_IN_HAND as usize, Stone::build(0)));
+    }
+
+    #[test]
+    fn game_play_to_the_end() {
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(11));
+
+        let winner = game.play(player::random_player::RandomPlayer, player::random_player::RandomPlayer);
+
+        assert_eq!(game.winner(), Some(winner));
+    }
+
+    #[test]
+    fn game_auto_claim() {
+        // Without auto claim, a player who never claims could not win.
+        struct NeverClaims;
+        impl PlayerTrait for NeverClaims {
+            fn choose_action(&self, hand: &Hand, board: &CardsOnBoard, player: Player) -> (usize, Stone) {
+                player::random_player::RandomPlayer.choose_action(hand, board, player)
+            }
+
+            fn claim(&self, _hand: &Hand, _board: &CardsOnBoard, _player: Player) -> Vec<Stone> {
+                Vec::new()
+            }
+        }
+
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(14));
+        game.set_auto_claim(Player::build(0), true);
+
+        assert!(game.auto_claims(Player::build(0)));
+        assert!(!game.auto_claims(Player::build(1)));
+
+        game.set_auto_claim(Player::build(1), true);
+
+        let winner = game.play(NeverClaims, NeverClaims);
+        let claimed = (0..NUM_OF_STONES).filter(|s| game.board().who_claimed(Stone::build(*s)) == Some(winner)).count();
+
+        assert_eq!(game.winner(), Some(winner));
+        assert!(claimed >= 3);
+    }
+
+    // The winner straight from the rules: three adjacent stones, or five stones.
+    fn reference_winner(board: &CardsOnBoard) -> Option<Player> {
+        [Player::build(0), Player::build(1)].into_iter().find(|player| {
+            let owned: Vec<bool> = (0..NUM_OF_STONES).map(|s| board.who_claimed(Stone::build(s)) == Some(*player)).collect();
+
+            owned.iter().filter(|owns| **owns).count() >= 5 || owned.windows(3).any(|adjacent| adjacent.iter().all(|owns| *owns))
+        })
+    }
+
+    // Cards on claimed stones, by stone index, as they were when the stones were claimed.
+    type ClaimedCards = [Option<usize>; NUM_OF_STONES as usize];
+
+    fn cards_on(game: &Game, stone: Stone) -> usize {
+        [Player::build(0), Player::build(1)].iter().map(|p| game.board().cards_of(*p, stone).len()).sum()
+    }
+
+    fn check_invariants(game: &Game, claimed: &ClaimedCards) {
+        let board = game.board();
+        let players = [Player::build(0), Player::build(1)];
+
+        let on_board = (0..NUM_OF_STONES).flat_map(|s| players.map(|p| board.cards_of(p, Stone::build(s)).iter()).into_iter().flatten());
+        let in_hands = players.iter().flat_map(|p| game.hand(*p).iter());
+        let in_deck = (0..game.deck_len()).map(|i| &game.deck[i]);
+
+        let mut seen = components::CardMask::empty();
+        let mut count = 0;
+
+        for card in on_board.chain(in_hands).chain(in_deck) {
+            assert!(!seen.contains(card), "{card} appears twice");
+            seen.insert(card);
+            count += 1;
+        }
+
+        assert_eq!(count, CARDS_IN_DECK as usize);
+        assert!(players.iter().all(|p| game.hand(*p).len() <= CARDS_IN_HAND as usize));
+
+        for stone in (0..NUM_OF_STONES).map(Stone::build) {
+            if let Some(cards) = claimed[stone.get_index()] {
+                assert_eq!(cards_on(game, stone), cards);
+            }
+        }
+
+        assert_eq!(game.winner(), reference_winner(board));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        // Random games, with claims attempted at random, legal or not.
+        #[test]
+        fn game_invariants(seed: u64, claims in vec(any::<bool>(), 1..64), choices in vec(any::<Index>(), 1..64)) {
+            let mut game = Game::with_rng(&mut StdRng::seed_from_u64(seed));
+            let mut claimed: ClaimedCards = [None; NUM_OF_STONES as usize];
+            let mut claims = claims.iter().cycle();
+            let mut choices = choices.iter().cycle();
+
+            for _ in 0..500 {
+                let player = game.to_move();
+                let legal_actions = game.legal_actions();
+
+                for stone in game.board().available_stones() {
+                    // A player who has to pass claims whatever they can, so that every game ends.
+                    if !legal_actions.is_empty() && !claims.next().unwrap() { continue; }
+
+                    let legal = game.board().claimable_stones(player).contains(&stone);
+                    let cards = cards_on(&game, stone);
+
+                    prop_assert_eq!(game.claim(stone), legal);
+
+                    if legal { claimed[stone.get_index()] = Some(cards); }
+                }
+
+                check_invariants(&game, &claimed);
+
+                if game.winner().is_some() { return Ok(()); }
+
+                if legal_actions.is_empty() {
+                    game.pass();
+                } else {
+                    game.apply_action(*choices.next().unwrap().get(&legal_actions));
+                }
+
+                check_invariants(&game, &claimed);
+            }
+
+            prop_assert!(false, "The game did not end");
+        }
+    }
+
+    #[test]
+    fn game_play_with_clock() {
+        use std::time::Duration;
+
+        struct SlowPlayer;
+        impl PlayerTrait for SlowPlayer {
+            fn choose_action(&self, hand: &Hand, board: &CardsOnBoard, player: Player) -> (usize, Stone) {
+                std::thread::sleep(Duration::from_millis(30));
+                player::random_player::RandomPlayer.choose_action(hand, board, player)
+            }
+        }
+
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(12));
+        let control = TimeControl::Fischer { base: Duration::from_secs(10), increment: Duration::from_millis(100) };
+        let (winner, clock) = game.play_with_clock(player::random_player::RandomPlayer, player::random_player::RandomPlayer, control);
+
+        assert_eq!(game.winner(), Some(winner));
+        assert!(clock.time_left(Player::build(0)).remaining > Duration::from_secs(10));
+
+        // The slow player runs out of time on their first move, which still reaches the board.
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(12));
+        let control = TimeControl::PerMove(Duration::from_millis(10));
+        let (winner, _) = game.play_with_clock(player::random_player::RandomPlayer, SlowPlayer, control);
+
+        assert_eq!(winner, Player::build(0));
+        assert_eq!(game.board().num_of_cards(), 2);
+    }
+
+    #[test]
+    fn game_zobrist() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(13));
+
+        // Swapping the hands, or passing the turn, changes the hash.
+        let mut swapped = game.clone();
+        std::mem::swap(&mut swapped.hand1, &mut swapped.hand2);
+
+        let mut passed = game.clone();
+        passed.to_move = passed.to_move.get_other();
+
+        assert_ne!(game.zobrist(), swapped.zobrist());
+        assert_ne!(game.zobrist(), passed.zobrist());
+
+        // Two placements in either order reach the same position.
+        let mut game1 = game.clone();
+        let mut game2 = game.clone();
+
+        game1.apply_action((0, Stone::build(1)));
+        game1.apply_action((0, Stone::build(2)));
+        game1.apply_action((1, Stone::build(3)));
+        game2.apply_action((1, Stone::build(3)));
+        game2.apply_action((0, Stone::build(2)));
+        game2.apply_action((0, Stone::build(1)));
+
+        assert_eq!(game1.board().notation(), game2.board().notation());
+        assert_eq!(game1.zobrist(), game2.zobrist());
+    }
+}
+
+#[derive(Clone)]
 pub struct Game {
     board: CardsOnBoard,
     deck: Deck,
     hand1: Hand,
     hand2: Hand,
+    to_move: Player,
+    // Players whose legal claims are made for them, by index (see `set_auto_claim`).
+    auto_claim: [bool; NUM_OF_PLAYERS as usize],
 }
 impl Game {
     pub fn new() -> Self {
-        let mut deck = Deck::new();
+        Self::with_rng(&mut thread_rng())
+    }
+
+    pub fn with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::with_deck(Deck::with_rng(rng))
+    }
+
+    // A game dealt from `deck` as is, for reproducing a deal regardless of the random number generator.
+    pub fn with_deck(mut deck: Deck) -> Self {
         let mut hand1 = Hand::new();
         let mut hand2 = Hand::new();
 
@@ -34,13 +296,105 @@ impl Game {
             deck,
             hand1,
             hand2,
+            to_move: Player::build(0),
+            auto_claim: [false; NUM_OF_PLAYERS as usize],
         }
     }
 
-    fn make_move<T: PlayerTrait>(&mut self, player: Player, p_type: &T) -> Option<Player> {
-        let hand = if player.get_player() == 1 {&mut self.hand1} else {&mut self.hand2};
+    pub fn board(&self) -> &CardsOnBoard { &self.board }
+
+    pub fn hand(&self, player: Player) -> &Hand {
+        if player.get_player() == 1 {&self.hand1} else {&self.hand2}
+    }
+
+    fn hand_mut(&mut self, player: Player) -> &mut Hand {
+        if player.get_player() == 1 {&mut self.hand1} else {&mut self.hand2}
+    }
+
+    pub fn deck_len(&self) -> usize { self.deck.len() }
+
+    pub fn to_move(&self) -> Player { self.to_move }
+
+    pub fn observation(&self, player: Player) -> Observation<'_> {
+        Observation::new(self.hand(player), &self.board, player)
+    }
+
+    pub fn winner(&self) -> Option<Player> { self.board.terminal_state() }
+
+    // Zobrist hash of the board, both hands and the player to move. The deck is left out:
+    // within a single deal, it follows from the cards already drawn.
+    pub fn zobrist(&self) -> u64 {
+        self.board.zobrist()
+            ^ self.hand1.zobrist()
+            ^ zobrist::second_hand(self.hand2.zobrist())
+            ^ zobrist::to_move_key(self.to_move)
+    }
+
+    // When set, `play` claims every stone `player` can legally claim at the start of their turns,
+    // instead of asking them with `Player::claim`. For players who cannot be trusted to claim, e.g. humans.
+    pub fn set_auto_claim(&mut self, player: Player, enabled: bool) {
+        self.auto_claim[player.get_index()] = enabled;
+    }
+
+    pub fn auto_claims(&self, player: Player) -> bool {
+        self.auto_claim[player.get_index()]
+    }
+
+    // Claims a stone for the player to move. Returns whether the claim was legal.
+    pub fn claim(&mut self, stone: Stone) -> bool {
+        self.board.claim(self.to_move, stone)
+    }
 
-        for stone in p_type.claim(hand, &self.board, player) {
+    // All (hand index, stone) pairs the player to move may play.
+    // An empty list means the player has to pass.
+    pub fn legal_actions(&self) -> Vec<(usize, Stone)> {
+        let hand_len = self.hand(self.to_move).len();
+
+        self.board
+            .available_stones_for(self.to_move)
+            .into_iter()
+            .flat_map(|stone| (0..hand_len).map(move |hand_index| (hand_index, stone)))
+            .collect()
+    }
+
+    // Plays a card of the player to move, draws a replacement and ends the turn.
+    pub fn apply_action(&mut self, (hand_index, stone): (usize, Stone)) {
+        let player = self.to_move;
+
+        if hand_index >= self.hand(player).len() || !self.board.available_stones_for(player).contains(&stone) {
+            panic!("Illegal action: card {} on stone {}", hand_index, stone.get_stone());
+        }
+
+        let card = self.hand_mut(player).remove(hand_index);
+
+        self.board.place_card(player, stone, card);
+
+        if let Some(card) = self.deck.draw() {
+            self.hand_mut(player).add(card);
+        }
+
+        self.to_move = player.get_other();
+    }
+
+    // Ends the turn of a player who has no legal action.
+    pub fn pass(&mut self) {
+        if !self.legal_actions().is_empty() {
+            panic!("Cannot pass while there are legal actions");
+        }
+
+        self.to_move = self.to_move.get_other();
+    }
+
+    fn make_move<T: PlayerTrait>(&mut self, p_type: &T, time: Option<&TimeLeft>) -> Option<Player> {
+        let player = self.to_move;
+
+        let stones = if self.auto_claims(player) {
+            self.board.claimable_stones(player)
+        } else {
+            p_type.claim(self.hand(player), &self.board, player)
+        };
+
+        for stone in stones {
             self.board.claim(player, stone);
         }
 
@@ -48,31 +402,69 @@ impl Game {
             return Some(p);
         }
 
-        if hand.len() > 0 && self.board.any_available_stones_for(player) {
-            let (hand_index, chosen_stone) = p_type.choose_action(hand, &self.board, player);
-
-            self.board.place_card(player, chosen_stone, hand.remove(hand_index));
+        if !self.hand(player).is_empty() && self.board.any_available_stones_for(player) {
+            let action = match time {
+                Some(time) => p_type.choose_action_timed(self.hand(player), &self.board, player, time),
+                None => p_type.choose_action(self.hand(player), &self.board, player),
+            };
 
-            if let Some(card) = self.deck.draw() {
-                hand.add(card);
-            }
+            self.apply_action(action);
+        } else {
+            self.pass();
         }
 
         None
     }
 
     pub fn play<T: PlayerTrait, S: PlayerTrait>(&mut self, player1: T, player2: S) -> Player {
+        player1.new_game();
+        player2.new_game();
+
         loop {
-            if let Some(player) = self.make_move(Player::build(0), &player1) {
+            let result = if self.to_move == Player::build(0) {
+                self.make_move(&player1, None)
+            } else {
+                self.make_move(&player2, None)
+            };
+
+            if let Some(player) = result {
                 return player;
             }
+        }
+    }
 
-            if let Some(player) = self.make_move(Player::build(1), &player2) {
-                return player;
+    // Like `play`, with every turn charged to the clock of the player to move.
+    // A player who runs out of time loses. Returns the winner with the final clocks.
+    pub fn play_with_clock<T: PlayerTrait, S: PlayerTrait>(&mut self, player1: T, player2: S, control: TimeControl) -> (Player, Clock) {
+        let mut clock = Clock::new(control);
+
+        player1.new_game();
+        player2.new_game();
+
+        loop {
+            let player = self.to_move;
+            let time = clock.time_left(player);
+            let start = Instant::now();
+
+            let result = if player == Player::build(0) {
+                self.make_move(&player1, Some(&time))
+            } else {
+                self.make_move(&player2, Some(&time))
+            };
+
+            if !clock.charge(player, start.elapsed()) {
+                return (player.get_other(), clock);
+            }
+
+            if let Some(winner) = result {
+                return (winner, clock);
             }
         }
     }
 }
+impl Default for Game {
+    fn default() -> Self { Self::new() }
+}
 impl fmt::Display for Game {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}\n{}\n{}", self.hand2, self.board, self.hand1)
@@ -1,9 +1,123 @@
+use std::{env, fs, io, process};
+use std::path::Path;
+
 use schotten::Game;
-use schotten::player::{random_player::RandomPlayer, analog_player::AnalogPlayer};
+use schotten::board::cards_on_board::tables;
+use schotten::experiment::{self, ExperimentConfig};
+use schotten::player::{self, random_player::RandomPlayer, analog_player::AnalogPlayer};
+use schotten::protocol::run_engine;
+use schotten::search::uct::UctConfig;
+use schotten::selfplay::{self, SelfPlayConfig};
 
 fn main() {
-    let mut game = Game::new();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        // Speak the engine protocol over stdin/stdout, see the `protocol` module.
+        Some("engine") => {
+            let name = args.get(1).map(String::as_str).unwrap_or("random");
+
+            let Some(bot) = player::by_name(name) else {
+                eprintln!("Unknown bot: {name}\nAvailable bots: {}", player::BOT_NAMES.join(", "));
+                process::exit(1);
+            };
+
+            let result = run_engine(name, &bot, io::stdin().lock(), io::stdout().lock());
+
+            if let Err(e) = result {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
+        // Generate training data, see the `selfplay` module.
+        Some("selfplay") => {
+            let Some(dir) = args.get(1) else {
+                eprintln!("Usage: schotten selfplay <output dir> [games] [iterations]");
+                process::exit(1);
+            };
+
+            let mut config = SelfPlayConfig::default();
+
+            if let Some(games) = args.get(2) {
+                config.games = games.parse().unwrap_or_else(|_| {
+                    eprintln!("Invalid number of games: {games}");
+                    process::exit(1);
+                });
+            }
+
+            if let Some(iterations) = args.get(3) {
+                config.search.iterations = iterations.parse().unwrap_or_else(|_| {
+                    eprintln!("Invalid number of iterations: {iterations}");
+                    process::exit(1);
+                });
+            }
+
+            match selfplay::run(&config, Path::new(dir)) {
+                Ok(positions) => println!("Wrote {positions} positions to {dir}"),
+                Err(e) => {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }
+            }
+        }
+        // Compare the ways of handling hidden information, see the `experiment` module.
+        Some("experiment") => {
+            let Some(dir) = args.get(1) else {
+                eprintln!("Usage: schotten experiment <output dir> [deals] [iterations]");
+                process::exit(1);
+            };
+
+            let mut config = ExperimentConfig::default();
+            let mut search = UctConfig::default();
+
+            if let Some(deals) = args.get(2) {
+                config.deals = deals.parse().unwrap_or_else(|_| {
+                    eprintln!("Invalid number of deals: {deals}");
+                    process::exit(1);
+                });
+            }
+
+            if let Some(iterations) = args.get(3) {
+                search.iterations = iterations.parse().unwrap_or_else(|_| {
+                    eprintln!("Invalid number of iterations: {iterations}");
+                    process::exit(1);
+                });
+            }
+
+            let reports = experiment::run_experiment(&experiment::standard_contenders(&search), &config);
+            let summary = experiment::summary_csv(&reports);
+
+            let written = fs::create_dir_all(dir)
+                .and_then(|_| fs::write(Path::new(dir).join("summary.csv"), &summary))
+                .and_then(|_| fs::write(Path::new(dir).join("moves.csv"), experiment::moves_csv(&reports)));
+
+            if let Err(e) = written {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+
+            print!("{summary}");
+        }
+        // Rebuild the sorted completion tables of resources/, see the `tables` module.
+        Some("tables") => {
+            let dir = args.get(1).map(String::as_str).unwrap_or("resources");
+
+            if let Err(e) = tables::write_tables(Path::new(dir)) {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+
+            println!("Wrote the tables to {dir}");
+        }
+        None => {
+            let mut game = Game::new();
 
-    println!("{:?}", game.play(AnalogPlayer, RandomPlayer));
-    println!("{}", game);
-}
\ No newline at end of file
+            println!("{:?}", game.play(AnalogPlayer, RandomPlayer));
+            println!("{}", game);
+        }
+        Some(other) => {
+            eprintln!("Unknown command: {other}\nUsage: schotten [engine [bot] | selfplay <output dir> [games] [iterations] | experiment <output dir> [deals] [iterations] | tables [output dir]]");
+            process::exit(1);
+        }
+    }
+}
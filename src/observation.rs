@@ -0,0 +1,99 @@
+use itertools::Itertools;
+
+use crate::board::cards_on_board::analysis::StoneStatus;
+use crate::board::cards_on_board::CardsOnBoard;
+use crate::board::hand::Hand;
+use crate::common::{CARDS_IN_DECK, CARDS_IN_HAND, NUM_OF_COLORS, NUM_OF_NUMS, NUM_OF_PLAYERS};
+use crate::components::{Card, CardMask, Player};
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng, Rng};
+
+    use crate::Game;
+
+    #[test]
+    fn test_hidden_information() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut game = Game::with_rng(&mut rng);
+
+        while game.winner().is_none() {
+            for player in (0..NUM_OF_PLAYERS).map(Player::build) {
+                let observation = game.observation(player);
+
+                assert_eq!(observation.deck_len(), game.deck_len());
+                assert_eq!(observation.opponent_hand_len(), game.hand(player.get_other()).len());
+                assert_eq!(observation.unseen_cards().len(), game.deck_len() + game.hand(player.get_other()).len());
+
+                for card in game.hand(player.get_other()).iter() {
+                    assert!(observation.unseen_cards().contains(card));
+                }
+            }
+
+            let actions = game.legal_actions();
+
+            if actions.is_empty() {
+                let stones = game.board().available_stones();
+
+                if stones.iter().all(|stone| !game.claim(*stone)) {
+                    game.pass();
+                }
+            } else {
+                game.apply_action(actions[rng.gen_range(0..actions.len())]);
+            }
+        }
+    }
+}
+
+// Everything a single player knows about the game: the board and their own hand.
+pub struct Observation<'a> {
+    pub player: Player,
+    pub hand: &'a Hand,
+    pub board: &'a CardsOnBoard,
+}
+impl<'a> Observation<'a> {
+    pub fn new(hand: &'a Hand, board: &'a CardsOnBoard, player: Player) -> Self {
+        Observation { player, hand, board }
+    }
+
+    pub fn deck_len(&self) -> usize {
+        // Every card played is replaced from the deck while it lasts.
+        let dealt = (NUM_OF_PLAYERS * CARDS_IN_HAND) as usize;
+
+        (CARDS_IN_DECK as usize - dealt).saturating_sub(self.board.num_of_cards())
+    }
+
+    pub fn opponent_hand_len(&self) -> usize {
+        CARDS_IN_DECK as usize - self.board.num_of_cards() - self.hand.len() - self.deck_len()
+    }
+
+    // Cards in the deck or in the opponent's hand.
+    pub fn unseen_cards(&self) -> Vec<Card> {
+        (1..=NUM_OF_NUMS)
+            .cartesian_product(1..=NUM_OF_COLORS)
+            .map(|(num, color)| Card::build(num, color))
+            .filter(|card| !self.board.is_present(card) && !self.hand.iter().any(|c| c == card))
+            .collect()
+    }
+
+    fn masks(&self) -> (CardMask, CardMask) {
+        (self.hand.iter().collect(), self.unseen_cards().iter().collect())
+    }
+
+    // See `CardsOnBoard::stone_statuses`.
+    pub fn stone_statuses(&self) -> Vec<StoneStatus> {
+        let (hand, unseen) = self.masks();
+
+        self.board.stone_statuses(self.player, &hand, &unseen)
+    }
+
+    // See `CardsOnBoard::dead_cards`.
+    pub fn dead_cards(&self) -> Vec<Card> {
+        let (hand, unseen) = self.masks();
+
+        self.board.dead_cards(self.player, &hand, &unseen)
+    }
+}
@@ -0,0 +1,201 @@
+// Odds of winning a single stone, for teaching and for bots weighing where to play.
+//
+// The cards missing on either side of the stone are dealt at random from the cards not yet on the board:
+// the unseen cards and the observer's own hand. The odds say how promising a stone looks,
+// not how it will be played, as neither player's choices are modeled.
+// Equal formations go to the player who completed the stone first (see `CardsOnBoard::advantage`).
+// While neither side is complete, who will complete first is unknown, and ties are split evenly.
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+use crate::board::cards_on_board::stonecards::StoneCards;
+use crate::common::{NUM_OF_PLAYERS, STONE_CARDS_LIMIT};
+use crate::components::{Card, Player, Stone};
+use crate::observation::Observation;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::board::cards_on_board::CardsOnBoard;
+    use crate::board::hand::Hand;
+
+    fn board_with(cards: &[(u8, Card)]) -> CardsOnBoard {
+        let mut board = CardsOnBoard::new();
+
+        for (player, card) in cards {
+            board.place_card(Player::build(*player), Stone::build(0), card.clone());
+        }
+
+        board
+    }
+
+    fn odds(board: &CardsOnBoard, stone: Stone) -> [f64; NUM_OF_PLAYERS as usize] {
+        let hand = Hand::new();
+
+        stone_win_probability_with_rng(stone, &Observation::new(&hand, board, Player::build(0)), &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn test_claimed() {
+        let mut board = board_with(&[
+            (0, Card::build(7, 1)), (0, Card::build(8, 1)), (0, Card::build(9, 1)),
+            (1, Card::build(1, 2)), (1, Card::build(2, 3)), (1, Card::build(4, 4)),
+        ]);
+
+        assert!(board.claim(Player::build(0), Stone::build(0)));
+        assert_eq!(odds(&board, Stone::build(0)), [1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_exact() {
+        // Player 1 holds a sum of 7. Player 2 needs one card to a pair of 1s: another 1 makes three of a kind,
+        // and a 6 or more beats the sum. A 5 ties, and ties go to player 1 who completed first.
+        let board = board_with(&[
+            (0, Card::build(1, 1)), (0, Card::build(2, 2)), (0, Card::build(4, 3)),
+            (1, Card::build(1, 3)), (1, Card::build(1, 4)),
+        ]);
+        let odds = odds(&board, Stone::build(0));
+        let pool = 54.0 - 5.0;
+
+        assert!((odds[1] - 27.0 / pool).abs() < 1e-9);
+        assert!((odds[0] + odds[1] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_advantage() {
+        // The only equal completion, 7-8-9 of another color, still loses to the color run completed first.
+        let board = board_with(&[
+            (0, Card::build(7, 1)), (0, Card::build(8, 1)), (0, Card::build(9, 1)),
+            (1, Card::build(7, 2)), (1, Card::build(8, 2)),
+        ]);
+
+        assert_eq!(odds(&board, Stone::build(0)), [1.0, 0.0]);
+
+        // Without a complete side, equal chances.
+        let board = board_with(&[(0, Card::build(7, 1)), (0, Card::build(8, 1)), (1, Card::build(7, 2)), (1, Card::build(8, 2))]);
+        let odds = odds(&board, Stone::build(0));
+
+        assert!((odds[0] - odds[1]).abs() < 1e-9);
+        assert!((odds[0] + odds[1] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_short_pool() {
+        // A complete side wins against a side that can never be completed.
+        assert_eq!(short_pool_shares([0, 3], 2), [1.0, 0.0]);
+        assert_eq!(short_pool_shares([2, 0], 1), [0.0, 1.0]);
+        // Only player 2 can ever complete the stone.
+        assert_eq!(short_pool_shares([3, 2], 2), [0.0, 1.0]);
+        // Either side alone can be completed, but not both.
+        assert_eq!(short_pool_shares([1, 2], 2), [0.5, 0.5]);
+        assert_eq!(short_pool_shares([3, 3], 2), [0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_monte_carlo() {
+        // Too many ways to fill two empty sides, so they are sampled.
+        let odds = odds(&CardsOnBoard::new(), Stone::build(4));
+
+        assert!((odds[0] - 0.5).abs() < 0.02, "{:?}", odds);
+        assert!((odds[0] + odds[1] - 1.0).abs() < 1e-9);
+    }
+}
+
+// Positions with at most this many ways to deal the missing cards are enumerated, others are sampled.
+pub const EXACT_LIMIT: u64 = 100_000;
+// Deals sampled when there are too many to enumerate.
+pub const SAMPLES: u32 = 20_000;
+
+// Probability of each player (by index) winning `stone`, as seen by the observing player.
+pub fn stone_win_probability(stone: Stone, observation: &Observation) -> [f64; NUM_OF_PLAYERS as usize] {
+    stone_win_probability_with_rng(stone, observation, &mut thread_rng())
+}
+
+pub fn stone_win_probability_with_rng<R: Rng + ?Sized>(
+    stone: Stone,
+    observation: &Observation,
+    rng: &mut R,
+) -> [f64; NUM_OF_PLAYERS as usize] {
+    let board = observation.board;
+    let players = [Player::build(0), Player::build(1)];
+
+    if let Some(owner) = board.who_claimed(stone) {
+        return players.map(|player| if player == owner { 1.0 } else { 0.0 });
+    }
+
+    let sides = players.map(|player| board.cards_of(player, stone));
+    let missing = sides.map(|cards| STONE_CARDS_LIMIT as usize - cards.len());
+
+    let mut pool = observation.unseen_cards();
+    pool.extend(observation.hand.iter().cloned());
+
+    // Not enough cards are left to complete both sides.
+    if pool.len() < missing[0] + missing[1] { return short_pool_shares(missing, pool.len()); }
+
+    // Shares of the stone going to each player once the missing cards are dealt.
+    let outcome = |first: &[&Card], second: &[&Card]| {
+        let complete = |cards: &StoneCards, extra: &[&Card]| {
+            let mut cards = cards.clone();
+            extra.iter().for_each(|card| cards.push((*card).clone()));
+            cards.strength()
+        };
+
+        let strengths = [complete(sides[0], first), complete(sides[1], second)];
+
+        match strengths[0].cmp(&strengths[1]) {
+            std::cmp::Ordering::Greater => [1.0, 0.0],
+            std::cmp::Ordering::Less => [0.0, 1.0],
+            std::cmp::Ordering::Equal => match board.advantage(stone) {
+                Some(player) => players.map(|p| if p == player { 1.0 } else { 0.0 }),
+                None => [0.5, 0.5],
+            },
+        }
+    };
+
+    let mut shares = [0.0; NUM_OF_PLAYERS as usize];
+    let mut deals = 0;
+    let mut add = |share: [f64; NUM_OF_PLAYERS as usize]| {
+        shares[0] += share[0];
+        shares[1] += share[1];
+        deals += 1;
+    };
+
+    if binomial(pool.len(), missing[0]) * binomial(pool.len() - missing[0], missing[1]) <= EXACT_LIMIT {
+        for first in pool.iter().combinations(missing[0]) {
+            let rest = pool.iter().filter(|card| !first.contains(card));
+
+            for second in rest.combinations(missing[1]) {
+                add(outcome(&first, &second));
+            }
+        }
+    } else {
+        for _ in 0..SAMPLES {
+            let (dealt, _) = pool.partial_shuffle(rng, missing[0] + missing[1]);
+            let dealt: Vec<&Card> = dealt.iter().collect();
+
+            add(outcome(&dealt[..missing[0]], &dealt[missing[0]..]));
+        }
+    }
+
+    shares.map(|share| share / deals as f64)
+}
+
+// Shares of the stone when the pool cannot complete both sides. A side that can never be completed loses
+// to one that can, which proves the stone once complete (see `CardsOnBoard::is_legal_claim`).
+// If either side may still be completed, who gets there first is unknown.
+fn short_pool_shares(missing: [usize; NUM_OF_PLAYERS as usize], pool: usize) -> [f64; NUM_OF_PLAYERS as usize] {
+    match missing.map(|cards| cards <= pool) {
+        [true, false] => [1.0, 0.0],
+        [false, true] => [0.0, 1.0],
+        _ => [0.5, 0.5],
+    }
+}
+
+fn binomial(n: usize, k: usize) -> u64 {
+    (0..k as u64).fold(1, |acc, i| acc * (n as u64 - i) / (i + 1))
+}
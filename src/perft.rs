@@ -0,0 +1,185 @@
+// Perft, as in chess engines: the number of move sequences of a given length from a position.
+// Comparing counts against reference positions catches changes to the rules of playing, passing and claiming.
+// Reference positions are kept in resources/perft.txt, one per line:
+//
+// <deck> ; <moves> ; <depth> ; <count>
+//
+// <deck> is every card in the order it is drawn, separated by spaces. Hands are dealt from it as in `Game::with_deck`.
+// <moves> are played from the start of the game, separated by commas, in the notation of `inspect::action_notation`.
+//
+// Moves are those of the search (see `search::actions`): a card played on a stone, or a pass when there is none.
+// Claims are not moves. Every legal claim is made at the start of a turn, as in `search::begin_turn`.
+// A game over before the given depth has no moves left, so the sequences leading to it are not counted.
+
+use std::str::FromStr;
+
+use crate::board::deck::Deck;
+use crate::common::CARDS_IN_DECK;
+use crate::components::Card;
+use crate::search::{self, inspect, SearchAction};
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::{CARDS_IN_HAND, NUM_OF_STONES};
+
+    fn corpus() -> Vec<PerftPosition> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/resources/perft.txt");
+
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_opening() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+        let actions = (CARDS_IN_HAND * NUM_OF_STONES) as u64;
+
+        assert_eq!(perft(&game, 0), 1);
+        assert_eq!(perft(&game, 1), actions);
+        assert_eq!(perft(&game, 2), actions * actions);
+    }
+
+    #[test]
+    fn test_divide() {
+        let position = &corpus()[1];
+        let divided = divide(&position.game, position.depth);
+
+        assert_eq!(divided.len() as u64, perft(&position.game, 1));
+        assert_eq!(divided.iter().map(|(_, count)| count).sum::<u64>(), position.count);
+    }
+
+    #[test]
+    fn test_corpus() {
+        for (i, position) in corpus().iter().enumerate() {
+            assert_eq!(perft(&position.game, position.depth), position.count, "position {} of the corpus", i + 1);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let line = corpus()[0].to_string();
+        let (deck, rest) = line.split_once(';').unwrap();
+
+        assert!(format!("{deck};{rest}").parse::<PerftPosition>().is_ok());
+        assert!(deck.parse::<PerftPosition>().is_err());
+        assert!(format!("Pu1 {deck};{rest}").parse::<PerftPosition>().is_err());
+        assert!(format!("{} ; ; 1 ; 54", deck.trim().split(' ').skip(1).collect::<Vec<_>>().join(" ")).parse::<PerftPosition>().is_err());
+        assert!(format!("{deck}; Ye0 3 ; 1 ; 54").parse::<PerftPosition>().is_err());
+        assert!(format!("{deck}; pass ; 1 ; 54").parse::<PerftPosition>().is_err());
+    }
+}
+
+// Number of move sequences of `depth` moves from `game`.
+pub fn perft(game: &Game, depth: u32) -> u64 {
+    if depth == 0 { return 1; }
+
+    let mut game = game.clone();
+
+    if search::begin_turn(&mut game).is_some() { return 0; }
+
+    let actions = search::actions(&game);
+
+    if depth == 1 { return actions.len() as u64; }
+
+    actions.iter().map(|action| perft(&after(&game, action), depth - 1)).sum()
+}
+
+// Perft below each move of the player to move, for finding where counts start to differ.
+pub fn divide(game: &Game, depth: u32) -> Vec<(SearchAction, u64)> {
+    let mut game = game.clone();
+
+    if depth == 0 || search::begin_turn(&mut game).is_some() { return Vec::new(); }
+
+    search::actions(&game)
+        .into_iter()
+        .map(|action| {
+            let count = perft(&after(&game, &action), depth - 1);
+
+            (action, count)
+        })
+        .collect()
+}
+
+fn after(game: &Game, action: &SearchAction) -> Game {
+    let mut game = game.clone();
+
+    search::apply(&mut game, action);
+
+    game
+}
+
+// A reference position with its perft count.
+#[derive(Clone)]
+pub struct PerftPosition {
+    // Every card, in the order drawn.
+    pub deck: Vec<Card>,
+    pub moves: Vec<SearchAction>,
+    pub game: Game,
+    pub depth: u32,
+    pub count: u64,
+}
+impl FromStr for PerftPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split(';').map(str::trim).collect();
+
+        if fields.len() != 4 {
+            return Err(format!("Expected 4 fields in a perft position, got {}", fields.len()));
+        }
+
+        let deck = fields[0].split_whitespace().map(str::parse).collect::<Result<Vec<Card>, String>>()?;
+
+        let mut sorted = deck.clone();
+        sorted.sort_by_key(|card| (card.num(), card.color()));
+        sorted.dedup();
+
+        if sorted.len() != deck.len() || deck.len() != CARDS_IN_DECK as usize {
+            return Err(String::from("The deck should hold every card exactly once"));
+        }
+
+        let moves = fields[1]
+            .split(',')
+            .filter(|action| !action.trim().is_empty())
+            .map(inspect::parse_action)
+            .collect::<Result<Vec<SearchAction>, String>>()?;
+
+        let mut game = Game::with_deck(Deck::from_cards(deck.iter().rev().cloned().collect()));
+
+        for action in moves.iter() {
+            if search::begin_turn(&mut game).is_some() {
+                return Err(String::from("The game is over before the last move"));
+            }
+
+            if !search::actions(&game).contains(action) {
+                return Err(format!("Illegal move: {}", inspect::action_notation(action)));
+            }
+
+            search::apply(&mut game, action);
+        }
+
+        let depth = fields[2].parse().map_err(|_| format!("Invalid depth: {}", fields[2]))?;
+        let count = fields[3].parse().map_err(|_| format!("Invalid count: {}", fields[3]))?;
+
+        Ok(PerftPosition { deck, moves, game, depth, count })
+    }
+}
+impl std::fmt::Display for PerftPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let deck: Vec<String> = self.deck.iter().map(Card::to_string).collect();
+        let moves: Vec<String> = self.moves.iter().map(inspect::action_notation).collect();
+
+        write!(f, "{} ; {} ; {} ; {}", deck.join(" "), moves.join(", "), self.depth, self.count)
+    }
+}
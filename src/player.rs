@@ -1,15 +1,72 @@
 pub mod random_player;
 pub mod analog_player;
+pub mod external_player;
+pub mod heuristic_player;
+pub mod uct_player;
+pub mod puct_player;
+pub mod search_player;
 
 use crate::board::cards_on_board::CardsOnBoard;
 use crate::board::hand::Hand;
 use crate::components::{Stone, Player as PlayerTag};
-use crate::common::NUM_OF_STONES;
+use crate::search::SearchResult;
+use crate::time_control::TimeLeft;
 
 pub trait Player {
     fn choose_action(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> (usize, Stone);
 
-    fn claim(&self, _hand: &Hand, _board: &CardsOnBoard, _player: PlayerTag) -> Vec<Stone> {
-        (0..NUM_OF_STONES).map(|s| Stone::build(s)).collect()
+    // Like `choose_action`, under a clock. Players who do not manage their time ignore it.
+    fn choose_action_timed(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag, _time: &TimeLeft) -> (usize, Stone) {
+        self.choose_action(hand, board, player)
     }
-}
\ No newline at end of file
+
+    // Stones to claim at the start of the turn. By default, every stone that can legally be claimed.
+    fn claim(&self, _hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> Vec<Stone> {
+        board.claimable_stones(player)
+    }
+
+    // Root statistics of the last decision, for players choosing by search.
+    fn last_search(&self) -> Option<SearchResult> {
+        None
+    }
+
+    // Called before the first move of every game, for players keeping state between moves.
+    fn new_game(&self) {}
+}
+impl<T: Player + ?Sized> Player for Box<T> {
+    fn choose_action(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> (usize, Stone) {
+        (**self).choose_action(hand, board, player)
+    }
+
+    fn choose_action_timed(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag, time: &TimeLeft) -> (usize, Stone) {
+        (**self).choose_action_timed(hand, board, player, time)
+    }
+
+    fn claim(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> Vec<Stone> {
+        (**self).claim(hand, board, player)
+    }
+
+    fn last_search(&self) -> Option<SearchResult> {
+        (**self).last_search()
+    }
+
+    fn new_game(&self) {
+        (**self).new_game()
+    }
+}
+
+// Names of the built-in bots, as accepted by `by_name`.
+pub const BOT_NAMES: [&str; 4] = ["random", "heuristic", "uct", "puct"];
+
+pub fn by_name(name: &str) -> Option<Box<dyn Player>> {
+    match name {
+        "random" => Some(Box::new(random_player::RandomPlayer)),
+        "heuristic" => Some(Box::new(heuristic_player::HeuristicPlayer::default())),
+        "uct" => Some(Box::new(uct_player::UctPlayer::new(uct_player::UctSearch::new(Default::default())))),
+        "puct" => Some(Box::new(puct_player::PuctPlayer::new(puct_player::PuctSearch::new(
+            Default::default(),
+            Box::new(crate::search::evaluator::HeuristicEvaluator),
+        )))),
+        _ => None,
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, io};
+use std::io;
 
 use super::{Player, Hand, CardsOnBoard, PlayerTag, Stone};
 
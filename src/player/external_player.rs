@@ -0,0 +1,284 @@
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Player, Hand, CardsOnBoard, PlayerTag, Stone};
+use crate::protocol::position_command;
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use crate::components::Card;
+
+    // A tiny engine that answers 'go' with `go`, claims stone 2, and counts new games in $n.
+    fn engine(go: &str) -> ExternalProcessPlayer {
+        let script = format!("n=0; while read cmd rest; do case $cmd in \
+            schotten) echo 'id name shell'; echo schottenok;; \
+            newgame) n=$((n+1));; \
+            go) {go};; \
+            claim) echo 'claim 2';; \
+            quit) exit 0;; \
+            esac; done");
+
+        let mut player = ExternalProcessPlayer::spawn("sh", &["-c", &script]).unwrap();
+        player.set_timeout(Duration::from_millis(200));
+
+        player
+    }
+
+    fn hand() -> Hand {
+        let mut hand = Hand::new();
+        hand.add(Card::build(3,3));
+        hand.add(Card::build(7,1));
+
+        hand
+    }
+
+    #[test]
+    fn test_external_process() {
+        let player = engine("echo 'info thinking'; echo 'move 1 4'");
+
+        assert_eq!(player.name(), "shell");
+
+        let board = CardsOnBoard::new();
+
+        assert_eq!(player.choose_action(&hand(), &board, PlayerTag::build(0)), (1, Stone::build(4)));
+        assert_eq!(player.claim(&hand(), &board, PlayerTag::build(0)), vec![Stone::build(2)]);
+        assert!(player.failure().is_none());
+    }
+
+    #[test]
+    fn test_new_game() {
+        let player = engine("echo \"move 0 $n\"");
+        let board = CardsOnBoard::new();
+
+        player.new_game();
+        player.new_game();
+
+        assert_eq!(player.choose_action(&hand(), &board, PlayerTag::build(0)), (0, Stone::build(2)));
+    }
+
+    #[test]
+    fn test_engine_failures() {
+        let board = CardsOnBoard::new();
+
+        // Silent, crashed, malformed and illegal answers.
+        for go in ["true", "exit 1", "echo 'move one'", "echo 'move 2 4'", "echo 'error busy'"] {
+            let player = engine(go);
+
+            assert_eq!(player.choose_action(&hand(), &board, PlayerTag::build(0)), (0, Stone::build(0)), "{go}");
+            assert!(player.failure().is_some(), "{go}");
+
+            // A failed engine is no longer asked anything.
+            assert_eq!(player.claim(&hand(), &board, PlayerTag::build(0)), board.claimable_stones(PlayerTag::build(0)));
+        }
+    }
+
+    #[test]
+    fn test_not_an_engine() {
+        assert!(ExternalProcessPlayer::spawn("sh", &["-c", "echo hello"]).is_err());
+    }
+}
+
+// How long an engine may take to answer, unless set with `set_timeout`.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// A player backed by an external process speaking the engine protocol (see the `protocol` module).
+//
+// An engine that crashes, does not answer in time, or sends a malformed or illegal answer is stopped and
+// given up on: from then on, the player makes the first legal move and every legal claim,
+// which amounts to forfeiting the game. The reason is kept, see `failure`.
+pub struct ExternalProcessPlayer {
+    name: String,
+    timeout: Duration,
+    process: RefCell<Process>,
+    failure: RefCell<Option<String>>,
+}
+impl ExternalProcessPlayer {
+    pub fn spawn(program: &str, args: &[&str]) -> io::Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("Child stdin should be piped.");
+        let stdout = BufReader::new(child.stdout.take().expect("Child stdout should be piped."));
+
+        // Lines are read on their own thread, so that waiting for them can time out.
+        let (sender, lines) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in stdout.lines() {
+                if sender.send(line).is_err() { break; }
+            }
+        });
+
+        let mut process = Process { child, stdin, lines };
+
+        let mut name = String::from(program);
+        let deadline = Instant::now() + DEFAULT_TIMEOUT;
+
+        let handshake = process.send("schotten").and_then(|_| loop {
+            let line = process.receive(deadline)?;
+
+            if let Some(id) = line.strip_prefix("id name ") {
+                name = String::from(id);
+            } else if line == "schottenok" {
+                break Ok(());
+            }
+        });
+
+        if let Err(e) = handshake {
+            let _ = process.child.kill();
+            let _ = process.child.wait();
+
+            return Err(e);
+        }
+
+        Ok(ExternalProcessPlayer { name, timeout: DEFAULT_TIMEOUT, process: RefCell::new(process), failure: RefCell::new(None) })
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    // Why the engine was given up on, if it was.
+    pub fn failure(&self) -> Option<String> {
+        self.failure.borrow().clone()
+    }
+
+    fn fail(&self, process: &mut Process, reason: String) {
+        let _ = process.child.kill();
+
+        *self.failure.borrow_mut() = Some(format!("Engine {}: {}", self.name, reason));
+    }
+
+    // Sends the position followed by `command`, and parses the arguments of the engine's answer.
+    // Returns None if the engine was given up on.
+    fn query<T, F>(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag, command: &str, answer: &str, parse: F) -> Option<T>
+    where
+        F: FnOnce(&[String]) -> Result<T, String>,
+    {
+        if self.failure.borrow().is_some() { return None; }
+
+        let mut process = self.process.borrow_mut();
+        let deadline = Instant::now() + self.timeout;
+
+        let response = process
+            .send(&position_command(hand, board, player))
+            .and_then(|_| process.send(command))
+            .and_then(|_| process.expect(answer, deadline))
+            .map_err(|e| format!("failed to answer '{command}': {e}"))
+            .and_then(|args| parse(&args));
+
+        match response {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                self.fail(&mut process, e);
+                None
+            }
+        }
+    }
+}
+impl Player for ExternalProcessPlayer {
+    fn choose_action(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> (usize, Stone) {
+        let parse = |args: &[String]| {
+            let parsed = match args {
+                [card, stone] => card.parse::<usize>().ok().zip(stone.parse::<u8>().ok()),
+                _ => None,
+            };
+
+            match parsed {
+                Some((card_index, stone_index)) if card_index < hand.len() => {
+                    match Stone::try_build(stone_index) {
+                        Ok(stone) if board.available_stones_for(player).contains(&stone) => Ok((card_index, stone)),
+                        _ => Err(format!("played on an unavailable stone: {stone_index}")),
+                    }
+                }
+                _ => Err(format!("sent an invalid move: {args:?}")),
+            }
+        };
+
+        self.query(hand, board, player, "go", "move", parse)
+            .unwrap_or_else(|| (0, board.available_stones_for(player)[0]))
+    }
+
+    fn claim(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> Vec<Stone> {
+        let parse = |args: &[String]| {
+            args.iter()
+                .map(|s| {
+                    s.parse::<u8>()
+                        .ok()
+                        .and_then(|s| Stone::try_build(s).ok())
+                        .ok_or_else(|| format!("sent an invalid stone: {s}"))
+                })
+                .collect()
+        };
+
+        self.query(hand, board, player, "claim", "claim", parse)
+            .unwrap_or_else(|| board.claimable_stones(player))
+    }
+
+    fn new_game(&self) {
+        if self.failure.borrow().is_some() { return; }
+
+        let mut process = self.process.borrow_mut();
+
+        if let Err(e) = process.send("newgame") {
+            self.fail(&mut process, format!("failed to start a new game: {e}"));
+        }
+    }
+}
+impl Drop for ExternalProcessPlayer {
+    fn drop(&mut self) {
+        let process = self.process.get_mut();
+
+        // Failing to send means the engine is already gone or broken.
+        if self.failure.get_mut().is_some() || process.send("quit").is_err() {
+            let _ = process.child.kill();
+        }
+
+        let _ = process.child.wait();
+    }
+}
+
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<io::Result<String>>,
+}
+impl Process {
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.stdin, "{line}")?;
+        self.stdin.flush()
+    }
+
+    fn receive(&mut self, deadline: Instant) -> io::Result<String> {
+        match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(line) => Ok(String::from(line?.trim_end())),
+            Err(RecvTimeoutError::Timeout) => Err(io::Error::new(io::ErrorKind::TimedOut, "Engine did not answer in time")),
+            Err(RecvTimeoutError::Disconnected) => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Engine closed its output")),
+        }
+    }
+
+    // Reads lines until one starting with `keyword`, and returns the rest of its tokens.
+    fn expect(&mut self, keyword: &str, deadline: Instant) -> io::Result<Vec<String>> {
+        loop {
+            let line = self.receive(deadline)?;
+            let mut tokens = line.split_whitespace();
+
+            match tokens.next() {
+                Some(first) if first == keyword => return Ok(tokens.map(String::from).collect()),
+                Some("error") => return Err(io::Error::new(io::ErrorKind::InvalidData, line)),
+                _ => continue,
+            }
+        }
+    }
+}
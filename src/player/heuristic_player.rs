@@ -0,0 +1,191 @@
+use super::{Player, Hand, CardsOnBoard, PlayerTag, Stone};
+use crate::board::cards_on_board::stonecards::Formation;
+use crate::common::{NUM_OF_NUMS, NUM_OF_STONES};
+use crate::components::CardMask;
+use crate::observation::Observation;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::components::Card;
+    use crate::Game;
+
+    fn hand_of(cards: &[Card]) -> Hand {
+        let mut hand = Hand::new();
+        for card in cards { hand.add(card.clone()); }
+        hand
+    }
+
+    #[test]
+    fn test_completes_color_run() {
+        let player = PlayerTag::build(0);
+        let mut board = CardsOnBoard::new();
+
+        board.place_card(player, Stone::build(4), Card::build(7, 2));
+        board.place_card(player, Stone::build(4), Card::build(8, 2));
+
+        let hand = hand_of(&[Card::build(1, 1), Card::build(3, 4), Card::build(9, 2), Card::build(2, 5)]);
+
+        assert_eq!(HeuristicPlayer::default().choose_action(&hand, &board, player), (2, Stone::build(4)));
+    }
+
+    #[test]
+    fn test_avoids_dumping() {
+        // Stone 1 is lost to a color run of the opponent, every other stone is empty.
+        let player = PlayerTag::build(0);
+        let mut board = CardsOnBoard::new();
+
+        for num in 7..=9 { board.place_card(player.get_other(), Stone::build(0), Card::build(num, 3)); }
+        board.place_card(player, Stone::build(0), Card::build(1, 1));
+
+        let hand = hand_of(&[Card::build(9, 1), Card::build(2, 6)]);
+        let scores = HeuristicPlayer::default().scores(&Observation::new(&hand, &board, player));
+        let score = |hand_index: usize, stone: u8| {
+            scores.iter().find(|(action, _)| *action == (hand_index, Stone::build(stone))).unwrap().1
+        };
+
+        assert!(score(1, 0) > score(0, 0));
+        assert!(score(0, 1) > score(0, 0));
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let player = PlayerTag::build(0);
+        let mut board = CardsOnBoard::new();
+
+        for num in 4..=6 { board.place_card(player, Stone::build(2), Card::build(num, 5)); }
+        for num in 1..=3 { board.place_card(player.get_other(), Stone::build(2), Card::build(num, 6)); }
+        assert!(board.claim(player, Stone::build(2)));
+
+        let hand = hand_of(&[Card::build(5, 1)]);
+        let weights = HeuristicWeights { formation: 0.0, threat: 0.0, ..HeuristicWeights::default() };
+        let action = HeuristicPlayer::new(weights).choose_action(&hand, &board, player);
+
+        assert!(action == (0, Stone::build(1)) || action == (0, Stone::build(3)));
+    }
+
+    #[test]
+    fn test_plays_legal_moves() {
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+        let bot = HeuristicPlayer::default();
+
+        while game.winner().is_none() {
+            let player = game.to_move();
+
+            for stone in bot.claim(game.hand(player), game.board(), player) {
+                game.claim(stone);
+            }
+
+            if game.winner().is_some() { break; }
+
+            if game.legal_actions().is_empty() {
+                game.pass();
+            } else {
+                let action = bot.choose_action(game.hand(player), game.board(), player);
+
+                assert!(game.legal_actions().contains(&action));
+                game.apply_action(action);
+            }
+        }
+    }
+}
+
+// Weights of the terms scoring a placement in `HeuristicPlayer`.
+#[derive(Clone, Debug)]
+pub struct HeuristicWeights {
+    // Best formation the player can still reach on the stone with the card, scaled to 0-1.
+    pub formation: f64,
+    // Best formation the opponent can still reach on the stone, scaled to 0-1.
+    // Rewards contesting threatened stones, as long as they can still be won.
+    pub threat: f64,
+    // Per neighbouring stone already claimed by the player, towards three adjacent stones.
+    pub adjacency: f64,
+    // Penalty per card number (scaled to 0-1) for placing on a stone that can no longer be won.
+    pub dump: f64,
+}
+impl Default for HeuristicWeights {
+    fn default() -> Self {
+        HeuristicWeights {
+            formation: 1.0,
+            threat: 0.3,
+            adjacency: 0.2,
+            dump: 1.0,
+        }
+    }
+}
+
+// Scores every placement with hand-written rules and plays the best one, no search involved.
+pub struct HeuristicPlayer {
+    weights: HeuristicWeights,
+}
+impl HeuristicPlayer {
+    pub fn new(weights: HeuristicWeights) -> Self {
+        HeuristicPlayer { weights }
+    }
+
+    // Scores of every legal placement.
+    pub fn scores(&self, observation: &Observation) -> Vec<((usize, Stone), f64)> {
+        let board = observation.board;
+        let player = observation.player;
+        let unseen: CardMask = observation.unseen_cards().iter().collect();
+        let max_strength = Formation::ColorRun { low: NUM_OF_NUMS - 2 }.rank() as f64;
+
+        let mut scores = Vec::new();
+
+        for stone in board.available_stones_for(player) {
+            let opponent = board
+                .cards_of(player.get_other(), stone)
+                .reachable_strengths(&unseen)
+                .map(|(worst, best)| (worst.rank(), best.rank()));
+
+            let adjacent_claims = [stone.get_index().checked_sub(1), Some(stone.get_index() + 1)]
+                .into_iter()
+                .flatten()
+                .filter(|s| *s < NUM_OF_STONES as usize && board.who_claimed(Stone::build(*s as u8)) == Some(player))
+                .count();
+
+            for (hand_index, card) in observation.hand.iter().enumerate() {
+                // The rest of the hand may complete the stone as well.
+                let mut available = unseen;
+                observation.hand.iter().filter(|c| *c != card).for_each(|c| available.insert(c));
+
+                let mut cards = board.cards_of(player, stone).clone();
+                cards.push(card.clone());
+
+                let own_best = cards.max_reachable_strength(&available).map_or(0, |best| best.rank());
+                let lost = opponent.is_some_and(|(worst, _)| own_best < worst);
+
+                let score = if lost {
+                    -self.weights.dump * card.num() as f64 / 9.0
+                } else {
+                    let threat = opponent.map_or(0, |(_, best)| best);
+
+                    self.weights.formation * own_best as f64 / max_strength
+                        + self.weights.threat * threat as f64 / max_strength
+                        + self.weights.adjacency * adjacent_claims as f64
+                };
+
+                scores.push(((hand_index, stone), score));
+            }
+        }
+
+        scores
+    }
+}
+impl Default for HeuristicPlayer {
+    fn default() -> Self {
+        Self::new(HeuristicWeights::default())
+    }
+}
+impl Player for HeuristicPlayer {
+    fn choose_action(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> (usize, Stone) {
+        self.scores(&Observation::new(hand, board, player))
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("A player with a legal action should not pass.")
+            .0
+    }
+}
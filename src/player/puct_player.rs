@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use super::search_player::{Search, SearchPlayer};
+use crate::observation::Observation;
+use crate::search::evaluator::Evaluator;
+use crate::search::puct::{search, PuctConfig};
+use crate::search::SearchResult;
+
+use rand::rngs::StdRng;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+
+    use crate::player::Player;
+    use crate::search::evaluator::HeuristicEvaluator;
+    use crate::Game;
+
+    #[test]
+    fn test_choose_action() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+        let player = game.to_move();
+        let config = PuctConfig { iterations: 50, determinizations: 2, ..PuctConfig::default() };
+        let puct = PuctPlayer::with_seed(PuctSearch::new(config, Box::new(HeuristicEvaluator)), 1);
+
+        let action = puct.choose_action(game.hand(player), game.board(), player);
+
+        assert!(game.legal_actions().contains(&action));
+        assert_eq!(puct.last_search().unwrap().best_action(game.hand(player)), Some(action));
+    }
+}
+
+// Plays the most visited move of a PUCT search guided by an `Evaluator`,
+// e.g. a network trained on self-play data loaded with `MlpEvaluator::load`.
+pub type PuctPlayer = SearchPlayer<PuctSearch>;
+
+pub struct PuctSearch {
+    config: PuctConfig,
+    evaluator: Box<dyn Evaluator>,
+}
+impl PuctSearch {
+    pub fn new(config: PuctConfig, evaluator: Box<dyn Evaluator>) -> Self {
+        PuctSearch { config, evaluator }
+    }
+}
+impl Search for PuctSearch {
+    fn search(&self, observation: &Observation, time_limit: Option<Duration>, rng: &mut StdRng) -> SearchResult {
+        match time_limit {
+            Some(limit) => {
+                let config = PuctConfig { iterations: u32::MAX, time_limit: Some(limit), ..self.config.clone() };
+
+                search(observation, &config, &self.evaluator, rng)
+            }
+            None => search(observation, &self.config, &self.evaluator, rng),
+        }
+    }
+}
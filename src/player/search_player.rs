@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use super::{Player, Hand, CardsOnBoard, PlayerTag, Stone};
+use crate::observation::Observation;
+use crate::search::SearchResult;
+use crate::time_control::TimeLeft;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+// A search run by a `SearchPlayer` for every decision.
+pub trait Search {
+    // Searches `observation`. With a time limit, the search runs for that long, however many iterations that makes.
+    fn search(&self, observation: &Observation, time_limit: Option<Duration>, rng: &mut StdRng) -> SearchResult;
+
+    // Called before the first move of every game, see `Player::new_game`.
+    fn new_game(&self) {}
+}
+
+// Plays the most visited move of a search, e.g. `UctPlayer` and `PuctPlayer`.
+pub struct SearchPlayer<S: Search> {
+    search: S,
+    rng: RefCell<StdRng>,
+    last_search: RefCell<Option<SearchResult>>,
+}
+impl<S: Search> SearchPlayer<S> {
+    pub fn new(search: S) -> Self {
+        Self::from_rng(search, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(search: S, seed: u64) -> Self {
+        Self::from_rng(search, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(search: S, rng: StdRng) -> Self {
+        SearchPlayer { search, rng: RefCell::new(rng), last_search: RefCell::new(None) }
+    }
+
+    fn decide(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag, time_limit: Option<Duration>) -> (usize, Stone) {
+        let observation = Observation::new(hand, board, player);
+        let result = self.search.search(&observation, time_limit, &mut self.rng.borrow_mut());
+
+        let action = result.best_action(hand).expect("A player with a legal action should not pass.");
+
+        *self.last_search.borrow_mut() = Some(result);
+
+        action
+    }
+}
+impl<S: Search> Player for SearchPlayer<S> {
+    fn choose_action(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag) -> (usize, Stone) {
+        self.decide(hand, board, player, None)
+    }
+
+    fn choose_action_timed(&self, hand: &Hand, board: &CardsOnBoard, player: PlayerTag, time: &TimeLeft) -> (usize, Stone) {
+        self.decide(hand, board, player, Some(time.allocate(board, player)))
+    }
+
+    fn last_search(&self) -> Option<SearchResult> {
+        self.last_search.borrow().clone()
+    }
+
+    fn new_game(&self) {
+        self.search.new_game();
+    }
+}
@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use super::search_player::{Search, SearchPlayer};
+use super::{CardsOnBoard, PlayerTag, Stone};
+use crate::common::NUM_OF_STONES;
+use crate::observation::Observation;
+use crate::search::opponent_model::OpponentModel;
+use crate::search::uct::{search, search_with_model, UctConfig};
+use crate::search::SearchResult;
+
+use rand::rngs::StdRng;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use rand::{Rng, SeedableRng};
+
+    use crate::components::Card;
+    use crate::player::Player;
+    use crate::Game;
+
+    #[test]
+    fn test_choose_action() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+        let player = game.to_move();
+        let uct = UctPlayer::with_seed(UctSearch::new(UctConfig { iterations: 50, determinizations: 2, ..UctConfig::default() }), 1);
+
+        assert!(uct.last_search().is_none());
+
+        let action = uct.choose_action(game.hand(player), game.board(), player);
+
+        assert!(game.legal_actions().contains(&action));
+        assert_eq!(uct.last_search().unwrap().best_action(game.hand(player)), Some(action));
+    }
+
+    #[test]
+    fn test_play_with_clock() {
+        use std::time::Duration;
+
+        use crate::player::random_player::RandomPlayer;
+        use crate::time_control::TimeControl;
+
+        let uct = UctPlayer::with_seed(UctSearch::new(UctConfig { determinizations: 2, ..UctConfig::default() }), 2);
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(3));
+
+        let (winner, clock) = game.play_with_clock(uct, RandomPlayer, TimeControl::Total(Duration::from_secs(2)));
+
+        // The search spreads its time over the game without running out.
+        assert_eq!(game.winner(), Some(winner));
+        assert!(clock.time_left(PlayerTag::build(0)).remaining > Duration::ZERO);
+        assert!(clock.time_left(PlayerTag::build(0)).remaining < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_opponent_model() {
+        // Counts the placements the model was shown.
+        #[derive(Debug)]
+        struct Counter(Arc<Mutex<usize>>);
+        impl OpponentModel for Counter {
+            fn observe(&mut self, _: &CardsOnBoard, _: PlayerTag, _: &Card, _: Stone) { *self.0.lock().unwrap() += 1; }
+            fn card_weight(&self, _: &Card) -> f64 { 1.0 }
+            fn reset(&mut self) { *self.0.lock().unwrap() = 0; }
+        }
+
+        let observed = Arc::new(Mutex::new(0));
+        let config = UctConfig { iterations: 20, determinizations: 2, ..UctConfig::default() };
+        let uct = UctPlayer::with_seed(UctSearch::new(config).with_opponent_model(Box::new(Counter(observed.clone()))), 0);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut game = Game::with_rng(&mut rng);
+
+        // The bot moves second, so it sees every placement of the opponent.
+        for _ in 0..4 {
+            let actions = game.legal_actions();
+            game.apply_action(actions[rng.gen_range(0..actions.len())]);
+
+            let player = game.to_move();
+            let action = uct.choose_action(game.hand(player), game.board(), player);
+            game.apply_action(action);
+        }
+
+        assert_eq!(*observed.lock().unwrap(), 4);
+
+        // Placements made while the bot does not choose, e.g. when it passes, are seen on its next turn.
+        for _ in 0..3 {
+            let actions = game.legal_actions();
+            game.apply_action(actions[rng.gen_range(0..actions.len())]);
+        }
+
+        let player = game.to_move();
+        uct.choose_action(game.hand(player), game.board(), player);
+
+        assert_eq!(*observed.lock().unwrap(), 6);
+
+        // A new game starts over from an empty board.
+        uct.new_game();
+        assert_eq!(*observed.lock().unwrap(), 0);
+
+        let mut game = Game::with_rng(&mut rng);
+        let actions = game.legal_actions();
+        game.apply_action(actions[0]);
+
+        let player = game.to_move();
+        uct.choose_action(game.hand(player), game.board(), player);
+
+        assert_eq!(*observed.lock().unwrap(), 1);
+    }
+}
+
+// Plays the most visited move of a determinized UCT search.
+pub type UctPlayer = SearchPlayer<UctSearch>;
+
+// With an opponent model, the model watches the opponent's placements and shapes the sampled hands.
+pub struct UctSearch {
+    config: UctConfig,
+    model: Option<RefCell<Box<dyn OpponentModel>>>,
+    // The board the model last looked at, to find the placements since. None before the first look of a game.
+    last_board: RefCell<Option<CardsOnBoard>>,
+}
+impl UctSearch {
+    pub fn new(config: UctConfig) -> Self {
+        UctSearch { config, model: None, last_board: RefCell::new(None) }
+    }
+
+    pub fn with_opponent_model(mut self, model: Box<dyn OpponentModel>) -> Self {
+        self.model = Some(RefCell::new(model));
+        self
+    }
+
+    // Shows the model the opponent's placements since it last looked at the board, each with the board it was made on.
+    fn observe_opponent(&self, model: &mut dyn OpponentModel, board: &CardsOnBoard, player: PlayerTag) {
+        let opponent = player.get_other();
+        let mut last_board = self.last_board.borrow_mut();
+        let mut before = last_board.take().unwrap_or_default();
+
+        // Own placements come first: the opponent has seen them.
+        for stone in (0..NUM_OF_STONES).map(Stone::build) {
+            for card in board.cards_of(player, stone).iter().skip(before.cards_of(player, stone).len()) {
+                before.place_card(player, stone, card.clone());
+            }
+        }
+
+        for stone in (0..NUM_OF_STONES).map(Stone::build) {
+            for card in board.cards_of(opponent, stone).iter().skip(before.cards_of(opponent, stone).len()) {
+                model.observe(&before, opponent, card, stone);
+                before.place_card(opponent, stone, card.clone());
+            }
+        }
+
+        *last_board = Some(board.clone());
+    }
+}
+impl Search for UctSearch {
+    fn search(&self, observation: &Observation, time_limit: Option<Duration>, rng: &mut StdRng) -> SearchResult {
+        let timed;
+        let config = match time_limit {
+            Some(limit) => {
+                timed = UctConfig { iterations: u32::MAX, time_limit: Some(limit), ..self.config.clone() };
+                &timed
+            }
+            None => &self.config,
+        };
+
+        match &self.model {
+            Some(model) => {
+                let mut model = model.borrow_mut();
+
+                self.observe_opponent(model.as_mut(), observation.board, observation.player);
+
+                search_with_model(observation, config, model.as_ref(), rng)
+            }
+            None => search(observation, config, rng),
+        }
+    }
+
+    fn new_game(&self) {
+        if let Some(model) = &self.model {
+            model.borrow_mut().reset();
+        }
+
+        *self.last_board.borrow_mut() = None;
+    }
+}
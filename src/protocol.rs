@@ -0,0 +1,288 @@
+// A line based text protocol for bots running as external processes, similar in spirit to UCI.
+//
+// The host (the process running the game) writes commands to the engine's stdin,
+// and the engine answers on its stdout, one line per message.
+//
+// Host -> engine                        | Engine -> host
+// --------------------------------------|-------------------------------------------
+// schotten                              | id name <name>, followed by schottenok
+// isready                               | readyok
+// newgame                               | (nothing), sent before every game
+// position <player> <hand> <board>      | (nothing)
+// go                                    | move <card index> <stone index>, or "error no legal move"
+// claim                                 | claim <stone index> <stone index> ...
+// quit                                  | (engine exits)
+//
+// <player> is the number of the player to act ('1' or '2').
+// <hand> is the player's hand as comma separated cards (e.g. "Pu1,Re5,Bl9"), or '-' when empty.
+// <board> is the board notation of `CardsOnBoard::notation`.
+// Card and stone indices are zero-indexed, card indices refer to the order of cards in <hand>.
+//
+// Positions are sent in full before every 'go' and 'claim', so an engine does not need to track the game.
+// An engine answers a malformed or unexpected command with a line of the form "error <message>".
+// Lines starting with "info" are ignored by the host and may be used for logging.
+// Search bots report the statistics of their search before every move, as "info" lines (see `search::inspect`).
+
+use std::io::{self, BufRead, Write};
+
+use crate::board::cards_on_board::CardsOnBoard;
+use crate::board::hand::Hand;
+use crate::common::NUM_OF_PLAYERS;
+use crate::components::Player;
+use crate::player::Player as PlayerTrait;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use crate::common::{NUM_OF_STONES, STONE_CARDS_LIMIT};
+    use crate::components::{Card, Stone};
+    use crate::player::uct_player::{UctPlayer, UctSearch};
+    use crate::search::uct::UctConfig;
+
+    struct FirstChoicePlayer;
+    impl PlayerTrait for FirstChoicePlayer {
+        fn choose_action(&self, _hand: &Hand, board: &CardsOnBoard, player: Player) -> (usize, Stone) {
+            (0, board.available_stones_for(player)[0])
+        }
+    }
+
+    fn run(input: &str) -> String {
+        let mut output: Vec<u8> = Vec::new();
+
+        run_engine("first", &FirstChoicePlayer, Cursor::new(input), &mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_hand_notation() {
+        let mut hand = Hand::new();
+
+        assert_eq!(hand_notation(&hand), "-");
+        assert_eq!(parse_hand("-").unwrap().len(), 0);
+
+        hand.add(Card::build(1,3));
+        hand.add(Card::build(5,1));
+
+        assert_eq!(hand_notation(&hand), "Re1,Pu5");
+
+        let parsed = parse_hand("Re1,Pu5").unwrap();
+
+        assert_eq!(parsed[0], Card::build(1,3));
+        assert_eq!(parsed[1], Card::build(5,1));
+
+        assert!(parse_hand("Re1,Re1").is_err());
+        assert!(parse_hand("Re1,Pu1,Br1,Ye1,Gr1,Bl1,Re2").is_err());
+        assert!(parse_hand("").is_err());
+    }
+
+    #[test]
+    fn test_position_round_trip() {
+        let mut board = CardsOnBoard::new();
+        board.place_card(Player::build(0), Stone::build(3), Card::build(4,4));
+
+        let mut hand = Hand::new();
+        hand.add(Card::build(9,6));
+
+        let command = position_command(&hand, &board, Player::build(1));
+
+        assert_eq!(command, format!("position 2 Bl9 {}", board.notation()));
+
+        let args: Vec<&str> = command.split_whitespace().skip(1).collect();
+        let (player, parsed_hand, parsed_board) = parse_position(&args).unwrap();
+
+        assert_eq!(player, Player::build(1));
+        assert_eq!(hand_notation(&parsed_hand), "Bl9");
+        assert_eq!(parsed_board.notation(), board.notation());
+    }
+
+    #[test]
+    fn test_engine_session() {
+        let board = CardsOnBoard::new();
+
+        let input = format!(
+            "schotten\nisready\nnewgame\ngo\nposition 1 Re1,Pu5 {}\ngo\nclaim\nfoo\nquit\nisready\n",
+            board.notation(),
+        );
+
+        let expected = "id name first\nschottenok\nreadyok\nerror no position was given\nmove 0 0\nclaim\nerror unknown command: foo\n";
+
+        assert_eq!(run(&input), expected);
+    }
+
+    #[test]
+    fn test_engine_search_info() {
+        let bot = UctPlayer::with_seed(UctSearch::new(UctConfig { iterations: 20, determinizations: 1, ..UctConfig::default() }), 0);
+        let hand = parse_hand("Re1,Pu5,Bl9,Gr2,Ye7,Br4").unwrap();
+
+        let input = format!("{}\ngo\n", position_command(&hand, &CardsOnBoard::new(), Player::build(1)));
+        let mut output = Vec::new();
+
+        run_engine("uct", &bot, Cursor::new(input), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].starts_with("info Move"));
+        assert!(lines[1..lines.len() - 1].iter().all(|line| line.starts_with("info ")));
+        assert!(lines[lines.len() - 1].starts_with("move "));
+    }
+
+    #[test]
+    fn test_engine_no_legal_move() {
+        let mut board = CardsOnBoard::new();
+
+        for stone in 0..NUM_OF_STONES {
+            for color in 1..=STONE_CARDS_LIMIT {
+                board.place_card(Player::build(0), Stone::build(stone), Card::build(stone + 1, color));
+            }
+        }
+
+        let bot = UctPlayer::with_seed(UctSearch::new(UctConfig { iterations: 20, determinizations: 1, ..UctConfig::default() }), 0);
+        let input = format!(
+            "position 1 - {}\ngo\nposition 1 Bl9 {}\ngo\n",
+            CardsOnBoard::new().notation(),
+            board.notation(),
+        );
+
+        let mut output = Vec::new();
+
+        run_engine("uct", &bot, Cursor::new(input), &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "error no legal move\nerror no legal move\n");
+    }
+
+    #[test]
+    fn test_engine_bad_position() {
+        let output = run("position 3 - //////// //////// --------- ---------\ngo\n");
+
+        assert!(output.starts_with("error "));
+        assert!(output.ends_with("error no position was given\n"));
+    }
+}
+
+pub fn hand_notation(hand: &Hand) -> String {
+    if hand.is_empty() {
+        String::from("-")
+    } else {
+        hand.iter().map(|card| card.to_string()).collect::<Vec<String>>().join(",")
+    }
+}
+
+pub fn parse_hand(notation: &str) -> Result<Hand, String> {
+    let mut hand = Hand::new();
+
+    if notation == "-" { return Ok(hand); }
+
+    for card_str in notation.split(',') {
+        let card = card_str.parse()?;
+
+        if hand.iter().any(|c| *c == card) {
+            return Err(format!("Card {card} appears more than once in hand"));
+        }
+        if hand.is_full() {
+            return Err(format!("Too many cards in hand: {notation}"));
+        }
+
+        hand.add(card);
+    }
+
+    Ok(hand)
+}
+
+pub fn parse_player(notation: &str) -> Result<Player, String> {
+    match notation.parse::<u8>() {
+        Ok(p) if (1..=NUM_OF_PLAYERS).contains(&p) => Ok(Player::build(p - 1)),
+        _ => Err(format!("Invalid player: {notation}")),
+    }
+}
+
+pub fn position_command(hand: &Hand, board: &CardsOnBoard, player: Player) -> String {
+    format!("position {} {} {}", player.get_player(), hand_notation(hand), board.notation())
+}
+
+// Parses the arguments of a 'position' command (everything after the command itself).
+pub fn parse_position(args: &[&str]) -> Result<(Player, Hand, CardsOnBoard), String> {
+    if args.len() < 2 {
+        return Err(String::from("Expected a player, a hand and a board"));
+    }
+
+    Ok((
+        parse_player(args[0])?,
+        parse_hand(args[1])?,
+        CardsOnBoard::from_notation(&args[2..].join(" "))?,
+    ))
+}
+
+// Serves `player` over the protocol, reading commands from `input` until 'quit' or end of input.
+pub fn run_engine<T: PlayerTrait + ?Sized, R: BufRead, W: Write>(name: &str, player: &T, input: R, mut output: W) -> io::Result<()> {
+    let mut position: Option<(Player, Hand, CardsOnBoard)> = None;
+
+    for line in input.lines() {
+        let line = line?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        let Some(command) = tokens.first() else { continue; };
+
+        match *command {
+            "schotten" => {
+                writeln!(output, "id name {name}")?;
+                writeln!(output, "schottenok")?;
+            }
+            "isready" => writeln!(output, "readyok")?,
+            "newgame" => {
+                position = None;
+                player.new_game();
+            }
+            "position" => {
+                match parse_position(&tokens[1..]) {
+                    Ok(parsed) => position = Some(parsed),
+                    Err(e) => {
+                        position = None;
+                        writeln!(output, "error {e}")?;
+                    }
+                }
+            }
+            "go" | "claim" => {
+                match &position {
+                    None => writeln!(output, "error no position was given")?,
+                    Some((p, hand, board)) if *command == "go" && (hand.is_empty() || !board.any_available_stones_for(*p)) => {
+                        writeln!(output, "error no legal move")?;
+                    }
+                    Some((p, hand, board)) if *command == "go" => {
+                        let (card_index, stone) = player.choose_action(hand, board, *p);
+
+                        if let Some(result) = player.last_search() {
+                            for line in result.to_string().lines() {
+                                writeln!(output, "info {line}")?;
+                            }
+                        }
+
+                        writeln!(output, "move {} {}", card_index, stone.get_index())?;
+                    }
+                    Some((p, hand, board)) => {
+                        let stones: Vec<String> = player
+                            .claim(hand, board, *p)
+                            .iter()
+                            .map(|stone| stone.get_index().to_string())
+                            .collect();
+
+                        let response = format!("claim {}", stones.join(" "));
+
+                        writeln!(output, "{}", response.trim_end())?;
+                    }
+                }
+            }
+            "quit" => break,
+            other => writeln!(output, "error unknown command: {other}")?,
+        }
+
+        output.flush()?;
+    }
+
+    Ok(())
+}
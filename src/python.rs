@@ -0,0 +1,214 @@
+// Python bindings, built with the `python` feature.
+//
+// Players and stones are zero-indexed, cards are (num, color) tuples.
+// Tests live in python/tests and run against the built extension module (see README).
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::common::{CARDS_IN_HAND, NUM_OF_PLAYERS, NUM_OF_STONES};
+use crate::components::{Card, Player, Stone};
+use crate::encode;
+use crate::player::{self, Player as PlayerTrait};
+use crate::Game;
+
+fn to_player(player: u8) -> PyResult<Player> {
+    if player < NUM_OF_PLAYERS {
+        Ok(Player::build(player))
+    } else {
+        Err(PyValueError::new_err(format!("Invalid player: {player}")))
+    }
+}
+
+fn to_stone(stone: u8) -> PyResult<Stone> {
+    Stone::try_build(stone).map_err(|_| PyValueError::new_err(format!("Invalid stone: {stone}")))
+}
+
+fn to_bot(name: &str) -> PyResult<Box<dyn PlayerTrait>> {
+    player::by_name(name).ok_or_else(|| PyValueError::new_err(format!("Unknown bot: {name}")))
+}
+
+fn card_tuple(card: &Card) -> (u8, u8) {
+    (card.num(), card.color())
+}
+
+#[pyclass(name = "Game")]
+pub struct PyGame {
+    game: Game,
+}
+
+#[pymethods]
+impl PyGame {
+    #[new]
+    #[pyo3(signature = (seed=None))]
+    fn new(seed: Option<u64>) -> Self {
+        let game = match seed {
+            Some(seed) => Game::with_rng(&mut StdRng::seed_from_u64(seed)),
+            None => Game::new(),
+        };
+
+        PyGame { game }
+    }
+
+    fn to_move(&self) -> u8 {
+        self.game.to_move().get_index() as u8
+    }
+
+    fn winner(&self) -> Option<u8> {
+        self.game.winner().map(|p| p.get_index() as u8)
+    }
+
+    fn deck_len(&self) -> usize {
+        self.game.deck_len()
+    }
+
+    fn hand(&self, player: u8) -> PyResult<Vec<(u8, u8)>> {
+        Ok(self.game.hand(to_player(player)?).iter().map(card_tuple).collect())
+    }
+
+    fn legal_actions(&self) -> Vec<(usize, u8)> {
+        self.game
+            .legal_actions()
+            .into_iter()
+            .map(|(hand_index, stone)| (hand_index, stone.get_index() as u8))
+            .collect()
+    }
+
+    fn apply_action(&mut self, hand_index: usize, stone: u8) -> PyResult<()> {
+        let action = (hand_index, to_stone(stone)?);
+
+        if !self.game.legal_actions().contains(&action) {
+            return Err(PyValueError::new_err(format!("Illegal action: ({hand_index}, {stone})")));
+        }
+
+        self.game.apply_action(action);
+
+        Ok(())
+    }
+
+    fn pass_turn(&mut self) -> PyResult<()> {
+        if !self.game.legal_actions().is_empty() {
+            return Err(PyValueError::new_err("Cannot pass while there are legal actions"));
+        }
+
+        self.game.pass();
+
+        Ok(())
+    }
+
+    fn is_legal_claim(&self, player: u8, stone: u8) -> PyResult<bool> {
+        Ok(self.game.board().is_legal_claim(to_player(player)?, to_stone(stone)?))
+    }
+
+    // A list of stone indices (a `Vec<u8>` would become `bytes`).
+    fn claimable_stones(&self, player: u8) -> PyResult<Vec<usize>> {
+        Ok(self.game.board().claimable_stones(to_player(player)?).into_iter().map(|stone| stone.get_index()).collect())
+    }
+
+    // Claims a stone for the player to move, returns whether the claim was legal.
+    fn claim(&mut self, stone: u8) -> PyResult<bool> {
+        Ok(self.game.claim(to_stone(stone)?))
+    }
+
+    fn bot_action(&self, name: &str) -> PyResult<(usize, u8)> {
+        let bot = to_bot(name)?;
+
+        if self.game.legal_actions().is_empty() {
+            return Err(PyValueError::new_err("There are no legal actions"));
+        }
+
+        let player = self.game.to_move();
+        let (hand_index, stone) = bot.choose_action(self.game.hand(player), self.game.board(), player);
+
+        Ok((hand_index, stone.get_index() as u8))
+    }
+
+    fn bot_claims(&self, name: &str) -> PyResult<Vec<u8>> {
+        let bot = to_bot(name)?;
+        let player = self.game.to_move();
+
+        Ok(bot
+            .claim(self.game.hand(player), self.game.board(), player)
+            .into_iter()
+            .map(|stone| stone.get_index() as u8)
+            .collect())
+    }
+
+    fn observation<'py>(&self, py: Python<'py>, player: u8) -> PyResult<Bound<'py, PyDict>> {
+        let observation = self.game.observation(to_player(player)?);
+        let board = observation.board;
+        let stones = || (0..NUM_OF_STONES).map(Stone::build);
+        let player_index = |p: Option<Player>| p.map(|p| p.get_index() as u8);
+
+        let cards: Vec<Vec<Vec<(u8, u8)>>> = (0..NUM_OF_PLAYERS)
+            .map(|p| stones().map(|s| board.cards_of(Player::build(p), s).iter().map(card_tuple).collect()).collect())
+            .collect();
+
+        let dict = PyDict::new(py);
+
+        dict.set_item("player", player)?;
+        dict.set_item("hand", observation.hand.iter().map(card_tuple).collect::<Vec<_>>())?;
+        dict.set_item("cards", cards)?;
+        dict.set_item("claims", stones().map(|s| player_index(board.who_claimed(s))).collect::<Vec<_>>())?;
+        dict.set_item("advantage", stones().map(|s| player_index(board.advantage(s))).collect::<Vec<_>>())?;
+        dict.set_item("deck_len", observation.deck_len())?;
+        dict.set_item("opponent_hand_len", observation.opponent_hand_len())?;
+        dict.set_item("unseen", observation.unseen_cards().iter().map(card_tuple).collect::<Vec<_>>())?;
+
+        Ok(dict)
+    }
+
+    // Feature vector of the observation of `player`, see src/encode.rs for the layout.
+    fn encode(&self, player: u8) -> PyResult<Vec<f32>> {
+        Ok(encode::encode(&self.game.observation(to_player(player)?)))
+    }
+
+    // Legal actions of the player to move, indexed by `action_index`.
+    fn legal_action_mask(&self) -> Vec<f32> {
+        encode::legal_action_mask(&self.game.observation(self.game.to_move()))
+    }
+
+    fn __str__(&self) -> String {
+        self.game.to_string()
+    }
+}
+
+#[pyfunction]
+fn bot_names() -> Vec<&'static str> {
+    player::BOT_NAMES.to_vec()
+}
+
+#[pyfunction]
+fn action_index(hand_index: usize, stone: u8) -> PyResult<usize> {
+    if hand_index >= CARDS_IN_HAND as usize {
+        return Err(PyValueError::new_err(format!("Invalid hand index: {hand_index}")));
+    }
+
+    Ok(encode::action_index(hand_index, to_stone(stone)?))
+}
+
+#[pyfunction]
+fn action_from_index(index: usize) -> PyResult<(usize, u8)> {
+    if index >= encode::NUM_OF_ACTIONS {
+        return Err(PyValueError::new_err(format!("Invalid action index: {index}")));
+    }
+
+    let (hand_index, stone) = encode::action_from_index(index);
+
+    Ok((hand_index, stone.get_index() as u8))
+}
+
+#[pymodule]
+fn schotten(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyGame>()?;
+    m.add_function(wrap_pyfunction!(bot_names, m)?)?;
+    m.add_function(wrap_pyfunction!(action_index, m)?)?;
+    m.add_function(wrap_pyfunction!(action_from_index, m)?)?;
+    m.add("ENCODING_LEN", encode::ENCODING_LEN)?;
+    m.add("NUM_OF_ACTIONS", encode::NUM_OF_ACTIONS)?;
+
+    Ok(())
+}
@@ -0,0 +1,360 @@
+pub mod determinize;
+pub mod evaluator;
+pub mod inspect;
+pub mod ismcts;
+pub mod opponent_model;
+pub mod playout;
+pub mod puct;
+pub mod rollout;
+pub mod solver;
+pub mod transposition;
+pub mod uct;
+
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::board::hand::Hand;
+use crate::components::{Card, Player, Stone};
+use crate::encode::{action_index, NUM_OF_ACTIONS};
+use crate::observation::Observation;
+use crate::Game;
+use playout::Playout;
+use rollout::RolloutPolicy;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_rollout_ends() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            let mut game = Game::with_rng(&mut rng);
+            let winner = rollout(&mut game, &rollout::UniformRollout, &mut rng);
+
+            assert_eq!(game.winner(), Some(winner));
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut game = Game::with_rng(&mut StdRng::seed_from_u64(1));
+        let player = game.to_move();
+        let card = game.hand(player)[3].clone();
+
+        let actions = actions(&game);
+        assert_eq!(actions.len(), game.legal_actions().len());
+        assert!(actions.contains(&Some((card.clone(), Stone::build(5)))));
+
+        apply(&mut game, &Some((card.clone(), Stone::build(5))));
+
+        assert_eq!(*game.board().cards_of(player, Stone::build(5)).iter().last().unwrap(), card);
+        assert_eq!(game.to_move(), player.get_other());
+    }
+
+    #[test]
+    fn test_budget() {
+        use std::time::Duration;
+
+        let untimed = Budget::new(20, None, 1);
+
+        assert!(untimed.proceed(19, || vec![19, 0]));
+        assert!(!untimed.proceed(20, || vec![20, 0]));
+
+        // 4 iterations are left at most: a lead of 8 is decided, a lead of 1 is not.
+        let timed = Budget::new(20, Some(Instant::now() + Duration::from_secs(60)), 1);
+
+        assert!(!timed.proceed(16, || vec![10, 2, 4]));
+        assert!(timed.proceed(16, || vec![6, 5, 5]));
+        assert!(timed.proceed(15, || vec![15, 0]));
+        assert!(!timed.proceed(16, || vec![16]));
+
+        // Other threads add their iterations.
+        assert!(Budget::new(20, Some(Instant::now() + Duration::from_secs(60)), 3).proceed(16, || vec![10, 2, 4]));
+
+        let late = Budget::new(20, Some(Instant::now()), 1);
+
+        assert!(late.proceed(0, Vec::new));
+        assert!(!late.proceed(1, || vec![1, 0]));
+    }
+
+    #[test]
+    fn test_visit_distribution() {
+        let mut hand = Hand::new();
+        hand.add(Card::build(1,1));
+        hand.add(Card::build(2,2));
+
+        let result = SearchResult {
+            player: Player::build(0),
+            children: vec![
+                ChildStats::new(Some((Card::build(2,2), Stone::build(3))), 30, 20.0),
+                ChildStats::new(Some((Card::build(1,1), Stone::build(0))), 10, 1.0),
+            ],
+        };
+
+        assert_eq!(result.best().visits, 30);
+        assert_eq!(result.best_action(&hand), Some((1, Stone::build(3))));
+
+        let distribution = result.visit_distribution(&hand);
+
+        assert_eq!(distribution.len(), NUM_OF_ACTIONS);
+        assert_eq!(distribution[action_index(1, Stone::build(3))], 0.75);
+        assert_eq!(distribution[action_index(0, Stone::build(0))], 0.25);
+        assert_eq!(distribution.iter().sum::<f32>(), 1.0);
+    }
+}
+
+// A move in search: a card played on a stone, or None when the player has to pass.
+// Cards are used instead of hand indices, since hands differ between determinizations.
+pub type SearchAction = Option<(Card, Stone)>;
+
+// Plies below the root kept in search results, for inspecting the search (see the `inspect` module).
+pub const KEPT_DEPTH: usize = 4;
+
+#[derive(Clone, Debug)]
+pub struct ChildStats {
+    pub action: SearchAction,
+    pub visits: u32,
+    // Sum of the rewards of the player making `action` over all visits. At the root, the searching player.
+    pub value: f64,
+    // Probability of the action according to the evaluator, for searches guided by one.
+    pub prior: Option<f32>,
+    // Statistics of the following actions, down to `KEPT_DEPTH` plies below the root.
+    pub children: Vec<ChildStats>,
+}
+impl ChildStats {
+    pub fn new(action: SearchAction, visits: u32, value: f64) -> Self {
+        ChildStats { action, visits, value, prior: None, children: Vec::new() }
+    }
+
+    pub fn mean_value(&self) -> f64 {
+        if self.visits == 0 { 0.0 } else { self.value / self.visits as f64 }
+    }
+
+    // Adds the statistics of the same action in another search, with the subtrees below it.
+    // Priors are averaged, weighted by visits.
+    fn merge(&mut self, other: &ChildStats) {
+        self.prior = match (self.prior, other.prior) {
+            (Some(p1), Some(p2)) if self.visits + other.visits > 0 => {
+                Some((p1 * self.visits as f32 + p2 * other.visits as f32) / (self.visits + other.visits) as f32)
+            }
+            (prior, other_prior) => prior.or(other_prior),
+        };
+
+        self.visits += other.visits;
+        self.value += other.value;
+
+        merge_children(&mut self.children, &other.children);
+    }
+}
+
+fn merge_children(children: &mut Vec<ChildStats>, others: &[ChildStats]) {
+    for other in others {
+        match children.iter_mut().find(|c| c.action == other.action) {
+            Some(child) => child.merge(other),
+            None => children.push(other.clone()),
+        }
+    }
+}
+
+// Statistics of the root of a search, for choosing a move and for training targets.
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub player: Player,
+    pub children: Vec<ChildStats>,
+}
+impl SearchResult {
+    pub fn best(&self) -> &ChildStats {
+        self.children
+            .iter()
+            .max_by_key(|child| child.visits)
+            .expect("A search should have at least one root child.")
+    }
+
+    pub fn best_action(&self, hand: &Hand) -> Option<(usize, Stone)> {
+        self.best().action.as_ref().map(|(card, stone)| (hand_index_of(hand, card), *stone))
+    }
+
+    // Visit counts of the root children normalized to a distribution, indexed by `action_index`.
+    pub fn visit_distribution(&self, hand: &Hand) -> Vec<f32> {
+        let mut distribution = vec![0.0; NUM_OF_ACTIONS];
+        let total: u32 = self.children.iter().map(|child| child.visits).sum();
+
+        if total == 0 { return distribution; }
+
+        for child in self.children.iter() {
+            if let Some((card, stone)) = &child.action {
+                distribution[action_index(hand_index_of(hand, card), *stone)] += child.visits as f32 / total as f32;
+            }
+        }
+
+        distribution
+    }
+
+    // Adds the statistics of `other`, e.g. from a search on another determinization.
+    pub fn merge(&mut self, other: &SearchResult) {
+        merge_children(&mut self.children, &other.children);
+    }
+}
+
+// How long a tree keeps iterating: up to `iterations`, and with a deadline, until the deadline
+// or until the most visited root move cannot be overtaken in the iterations the time left allows.
+struct Budget {
+    iterations: u32,
+    deadline: Option<Instant>,
+    start: Instant,
+    // Threads iterating on the same tree, each with its own budget.
+    threads: u32,
+}
+impl Budget {
+    // How often a timed search checks whether its best move is decided.
+    const CHECK_INTERVAL: u32 = 16;
+
+    fn new(iterations: u32, deadline: Option<Instant>, threads: u32) -> Self {
+        Budget { iterations, deadline, start: Instant::now(), threads: threads.max(1) }
+    }
+
+    // Whether to run another iteration after `done`, where `root_visits` gives the visits of the root children.
+    // Every tree gets at least one iteration.
+    fn proceed<F: FnOnce() -> Vec<u32>>(&self, done: u32, root_visits: F) -> bool {
+        if done >= self.iterations { return false; }
+
+        let Some(deadline) = self.deadline else { return true; };
+
+        let now = Instant::now();
+
+        if done == 0 { return true; }
+        if now >= deadline { return false; }
+        if done % Self::CHECK_INTERVAL != 0 { return true; }
+
+        let rate = done as f64 / now.duration_since(self.start).as_secs_f64();
+        let left = ((rate * (deadline - now).as_secs_f64()) as u32).min(self.iterations - done).saturating_mul(self.threads);
+
+        let mut visits = root_visits();
+        visits.sort_unstable_by(|a, b| b.cmp(a));
+
+        match visits.as_slice() {
+            [best, second, ..] => best - second <= left,
+            _ => false,
+        }
+    }
+}
+
+// Part `i` of `total` split in `parts`, spreading the remainder so the parts add up exactly.
+fn share(total: u32, parts: u32, i: u32) -> u32 {
+    total / parts + u32::from(i < total % parts)
+}
+
+// Statistics of a search tree whose nodes are indices, with the root at 0.
+trait TreeStats {
+    // The player to move at the root.
+    fn root_player(&self) -> Player;
+
+    // The children of `node`, with the action leading to each.
+    fn edges(&self, node: usize) -> Vec<(&SearchAction, usize)>;
+
+    // Statistics of `node` reached by `action`, without its children.
+    fn node_stats(&self, action: &SearchAction, node: usize) -> ChildStats;
+
+    // Statistics of the edge of `action` to `node`, with the subtree below down to `depth` more plies.
+    fn stats(&self, action: &SearchAction, node: usize, depth: usize) -> ChildStats {
+        let mut stats = self.node_stats(action, node);
+
+        if depth > 0 {
+            stats.children = self.edges(node).into_iter().map(|(action, child)| self.stats(action, child, depth - 1)).collect();
+        }
+
+        stats
+    }
+
+    fn root_visits(&self) -> Vec<u32> {
+        self.edges(0).into_iter().map(|(action, child)| self.node_stats(action, child).visits).collect()
+    }
+
+    fn root_stats(&self) -> SearchResult {
+        SearchResult {
+            player: self.root_player(),
+            children: self.edges(0).into_iter().map(|(action, child)| self.stats(action, child, KEPT_DEPTH - 1)).collect(),
+        }
+    }
+}
+
+// Plays a won endgame exactly (see `solver::solve_observation`) before a search started at `start` begins.
+// The solver gets at most half of the time limit, leaving the rest to the search if it gives up.
+fn solve_endgame(observation: &Observation, endgame_nodes: u64, time_limit: Option<Duration>, start: Instant) -> Option<SearchResult> {
+    solver::solve_observation(observation, endgame_nodes, time_limit.map(|limit| start + limit / 2))
+}
+
+fn hand_index_of(hand: &Hand, card: &Card) -> usize {
+    hand.iter()
+        .position(|c| c == card)
+        .expect("Searched card should be in hand.")
+}
+
+// Starts the turn of the player to move by claiming every stone they legally can.
+// Returns the winner if the claims ended the game.
+pub fn begin_turn(game: &mut Game) -> Option<Player> {
+    for stone in game.board().available_stones() {
+        game.claim(stone);
+    }
+
+    game.winner()
+}
+
+pub fn actions(game: &Game) -> Vec<SearchAction> {
+    let hand = game.hand(game.to_move());
+    let legal_actions = game.legal_actions();
+
+    if legal_actions.is_empty() {
+        vec![None]
+    } else {
+        legal_actions
+            .into_iter()
+            .map(|(hand_index, stone)| Some((hand[hand_index].clone(), stone)))
+            .collect()
+    }
+}
+
+pub fn apply(game: &mut Game, action: &SearchAction) {
+    match action {
+        Some((card, stone)) => {
+            let hand_index = hand_index_of(game.hand(game.to_move()), card);
+
+            game.apply_action((hand_index, *stone));
+        }
+        None => game.pass(),
+    }
+}
+
+// The winner of a simulation from `game` with moves chosen by `policy`.
+// Uniform policies are played by a `Playout`, which chooses the same moves without allocating.
+pub fn simulate<R: Rng + ?Sized>(mut game: Game, policy: &dyn RolloutPolicy, rng: &mut R) -> Player {
+    if policy.is_uniform() {
+        Playout::new(&game).play(rng)
+    } else {
+        rollout(&mut game, policy, rng)
+    }
+}
+
+// Plays moves chosen by `policy` until the game ends, and returns the winner.
+pub fn rollout<R: Rng + ?Sized>(game: &mut Game, policy: &dyn RolloutPolicy, mut rng: &mut R) -> Player {
+    loop {
+        if let Some(winner) = begin_turn(game) {
+            return winner;
+        }
+
+        let legal_actions = game.legal_actions();
+
+        if legal_actions.is_empty() {
+            game.pass();
+        } else {
+            let action = policy.choose(game, &legal_actions, &mut rng);
+
+            game.apply_action(action);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::board::deck::Deck;
+use crate::board::hand::Hand;
+use crate::components::Card;
+use super::opponent_model::OpponentModel;
+use crate::observation::Observation;
+use crate::Game;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::board::cards_on_board::CardsOnBoard;
+    use crate::components::{Player, Stone};
+
+    #[test]
+    fn test_determinize() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = Game::with_rng(&mut rng);
+
+        for _ in 0..11 {
+            let actions = game.legal_actions();
+            game.apply_action(actions[rng.gen_range(0..actions.len())]);
+        }
+
+        let player = Player::build(1);
+        let observation = game.observation(player);
+        let determinized = determinize(&observation, &mut rng);
+
+        assert_eq!(determinized.to_move(), game.to_move());
+        assert_eq!(determinized.deck_len(), game.deck_len());
+        assert_eq!(determinized.board().notation(), game.board().notation());
+        assert_eq!(determinized.hand(player).to_string(), game.hand(player).to_string());
+        assert_eq!(determinized.hand(player.get_other()).len(), game.hand(player.get_other()).len());
+
+        let unseen = observation.unseen_cards();
+
+        for card in determinized.hand(player.get_other()).iter() {
+            assert!(unseen.contains(card));
+        }
+    }
+
+    #[test]
+    fn test_determinize_with_model() {
+        // A model certain that the opponent holds no card of Purple.
+        #[derive(Debug)]
+        struct NoPurple;
+        impl OpponentModel for NoPurple {
+            fn observe(&mut self, _: &CardsOnBoard, _: Player, _: &Card, _: Stone) {}
+            fn card_weight(&self, card: &Card) -> f64 { if card.color() == 1 { 0.0 } else { 1.0 } }
+            fn reset(&mut self) {}
+        }
+
+        let mut rng = StdRng::seed_from_u64(2);
+        let game = Game::with_rng(&mut rng);
+        let player = game.to_move();
+        let observation = game.observation(player);
+
+        for _ in 0..10 {
+            let determinized = determinize_with_model(&observation, &NoPurple, &mut rng);
+
+            assert_eq!(determinized.deck_len(), game.deck_len());
+            assert_eq!(determinized.hand(player).to_string(), game.hand(player).to_string());
+            assert!(determinized.hand(player.get_other()).iter().all(|card| card.color() != 1));
+        }
+    }
+}
+
+// Samples a full game consistent with what the observing player knows,
+// by dealing the unseen cards uniformly at random between the opponent's hand and the deck.
+// The observing player is the one to move in the sampled game.
+pub fn determinize<R: Rng + ?Sized>(observation: &Observation, rng: &mut R) -> Game {
+    let mut unseen = observation.unseen_cards();
+
+    unseen.shuffle(rng);
+
+    let opponent_hand = unseen.drain(..observation.opponent_hand_len()).collect();
+
+    deal(observation, opponent_hand, unseen)
+}
+
+// Number of hands drawn by `determinize_with_model`, before choosing one by weight.
+pub const MODEL_CANDIDATES: usize = 32;
+
+// Like `determinize`, but the opponent's hand is chosen among hands sampled from `model`, in proportion to their weights.
+pub fn determinize_with_model<R: Rng + ?Sized>(observation: &Observation, model: &dyn OpponentModel, mut rng: &mut R) -> Game {
+    let candidates = model.sample_hands(observation, MODEL_CANDIDATES, &mut rng as &mut dyn RngCore);
+
+    let opponent_hand = match WeightedIndex::new(candidates.iter().map(|hand| hand.weight)) {
+        Ok(distribution) => candidates[distribution.sample(rng)].cards.clone(),
+        // Every hand has weight 0, the model rules out everything it was shown.
+        Err(_) => candidates[0].cards.clone(),
+    };
+
+    let mut deck: Vec<Card> = observation.unseen_cards().into_iter().filter(|card| !opponent_hand.contains(card)).collect();
+
+    deck.shuffle(rng);
+
+    deal(observation, opponent_hand, deck)
+}
+
+// The only game consistent with `observation` once the deck is empty: the unseen cards are the opponent's hand.
+pub fn reveal(observation: &Observation) -> Game {
+    assert_eq!(observation.deck_len(), 0, "Hidden cards can only be revealed with an empty deck.");
+
+    deal(observation, observation.unseen_cards(), Vec::new())
+}
+
+// The game where the opponent holds `opponent_hand` and `deck` is drawn from its end.
+fn deal(observation: &Observation, opponent_hand: Vec<Card>, deck: Vec<Card>) -> Game {
+    let mut hand = Hand::new();
+
+    for card in opponent_hand {
+        hand.add(card);
+    }
+
+    let own_hand = observation.hand.clone();
+    let (hand1, hand2) = if observation.player.get_index() == 0 {
+        (own_hand, hand)
+    } else {
+        (hand, own_hand)
+    };
+
+    Game {
+        board: observation.board.clone(),
+        deck: Deck::from_cards(deck),
+        hand1,
+        hand2,
+        to_move: observation.player,
+        auto_claim: Default::default(),
+    }
+}
@@ -0,0 +1,126 @@
+pub mod mlp;
+
+use crate::common::NUM_OF_STONES;
+use crate::components::Stone;
+use crate::encode::{action_index, legal_action_mask, NUM_OF_ACTIONS};
+use crate::observation::Observation;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::board::cards_on_board::CardsOnBoard;
+    use crate::board::hand::Hand;
+    use crate::components::{Card, Player};
+
+    #[test]
+    fn test_normalized_priors() {
+        let mut priors = vec![0.0; NUM_OF_ACTIONS];
+        let mask: Vec<f32> = (0..NUM_OF_ACTIONS).map(|i| if i < 4 { 1.0 } else { 0.0 }).collect();
+
+        priors[0] = 3.0;
+        priors[1] = 1.0;
+        priors[10] = 5.0;
+
+        assert_eq!(&normalized_priors(&priors, &mask)[..5], &[0.75, 0.25, 0.0, 0.0, 0.0]);
+
+        // Without any weight on legal actions, fall back to uniform priors.
+        assert_eq!(&normalized_priors(&vec![0.0; NUM_OF_ACTIONS], &mask)[..5], &[0.25, 0.25, 0.25, 0.25, 0.0]);
+    }
+
+    #[test]
+    fn test_heuristic_evaluator() {
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        for num in 1..=3 { board.place_card(player.get_other(), Stone::build(0), Card::build(num, 1)); }
+        for num in 4..=6 { board.place_card(player, Stone::build(0), Card::build(num, 2)); }
+        assert!(board.claim(player, Stone::build(0)));
+
+        board.place_card(player, Stone::build(1), Card::build(7, 3));
+
+        let mut hand = Hand::new();
+        hand.add(Card::build(8, 3));
+        hand.add(Card::build(1, 5));
+
+        let evaluation = HeuristicEvaluator.evaluate(&Observation::new(&hand, &board, player));
+
+        assert!(evaluation.value > 0.0);
+        assert_eq!(evaluation.priors[action_index(0, Stone::build(0))], 0.0);
+        // 8 of Red fits the 7 of Red on stone 2 better than anywhere else.
+        assert!(evaluation.priors[action_index(0, Stone::build(1))] > evaluation.priors[action_index(0, Stone::build(2))]);
+        assert!(evaluation.priors[action_index(0, Stone::build(1))] > evaluation.priors[action_index(1, Stone::build(1))]);
+    }
+}
+
+// Output of an `Evaluator` for the player to move.
+#[derive(Clone, Debug)]
+pub struct Evaluation {
+    // Weights of the actions, indexed by `action_index`. Weights of illegal actions are ignored.
+    pub priors: Vec<f32>,
+    // Expected outcome for the observing player, between -1 (loss) and 1 (win).
+    pub value: f32,
+}
+
+// Guides `puct::search` with action priors and leaf values.
+pub trait Evaluator {
+    fn evaluate(&self, observation: &Observation) -> Evaluation;
+}
+impl<T: Evaluator + ?Sized> Evaluator for Box<T> {
+    fn evaluate(&self, observation: &Observation) -> Evaluation {
+        (**self).evaluate(observation)
+    }
+}
+
+// Keeps the weights of the legal actions of `mask` (see `legal_action_mask`) and normalizes them to a distribution.
+pub fn normalized_priors(priors: &[f32], mask: &[f32]) -> Vec<f32> {
+    let mut normalized: Vec<f32> = priors.iter().zip(mask).map(|(p, m)| if *m > 0.0 { p.max(0.0) } else { 0.0 }).collect();
+    let total: f32 = normalized.iter().sum();
+
+    if total > 0.0 {
+        normalized.iter_mut().for_each(|p| *p /= total);
+    } else {
+        let legal = mask.iter().filter(|m| **m > 0.0).count() as f32;
+
+        for (p, m) in normalized.iter_mut().zip(mask) {
+            if *m > 0.0 { *p = 1.0 / legal; }
+        }
+    }
+
+    normalized
+}
+
+// A cheap hand-written evaluator, for searching without a trained network.
+// Placements are favored by how well the card fits the cards already on the stone,
+// and positions are valued by the difference in claimed stones.
+pub struct HeuristicEvaluator;
+impl Evaluator for HeuristicEvaluator {
+    fn evaluate(&self, observation: &Observation) -> Evaluation {
+        let board = observation.board;
+        let mask = legal_action_mask(observation);
+        let mut priors = vec![0.0; NUM_OF_ACTIONS];
+
+        for (hand_index, card) in observation.hand.iter().enumerate() {
+            for stone in (0..NUM_OF_STONES).map(Stone::build) {
+                let index = action_index(hand_index, stone);
+
+                if mask[index] == 0.0 { continue; }
+
+                let fit: usize = board
+                    .cards_of(observation.player, stone)
+                    .iter()
+                    .map(|c| usize::from(c.color() == card.color()) + usize::from(c.num().abs_diff(card.num()) <= 2))
+                    .sum();
+
+                priors[index] = 1.0 + fit as f32 + card.num() as f32 / 9.0;
+            }
+        }
+
+        let claims: i32 = (0..NUM_OF_STONES)
+            .filter_map(|s| board.who_claimed(Stone::build(s)))
+            .map(|p| if p == observation.player { 1 } else { -1 })
+            .sum();
+
+        Evaluation { priors: normalized_priors(&priors, &mask), value: (claims as f32 / 2.0).tanh() }
+    }
+}
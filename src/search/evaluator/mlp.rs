@@ -0,0 +1,228 @@
+// A multilayer perceptron evaluator, run on the CPU.
+//
+// The network maps `encode::encode` of an observation (ENCODING_LEN inputs) through fully connected
+// layers with ReLU activations in between to NUM_OF_ACTIONS + 1 outputs:
+// policy logits indexed by `action_index`, followed by the value before tanh.
+//
+// Weights files are plain text, whitespace separated, with '#' starting a comment until the end of the line:
+//
+//     layers <number of layers>
+//     <inputs> <outputs>              (for every layer)
+//     <outputs * inputs weights>      (row major: the weights of the first output come first)
+//     <outputs biases>
+
+use std::fs;
+use std::path::Path;
+
+use super::{normalized_priors, Evaluation, Evaluator};
+use crate::encode::{encode, legal_action_mask, ENCODING_LEN, NUM_OF_ACTIONS};
+use crate::observation::Observation;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::components::Stone;
+    use crate::encode::action_index;
+    use crate::Game;
+
+    // A single layer ignoring its inputs, with the given biases as outputs.
+    fn constant_network(biases: &[f32]) -> String {
+        let zeros = vec!["0"; ENCODING_LEN * (NUM_OF_ACTIONS + 1)].join(" ");
+        let biases: Vec<String> = biases.iter().map(f32::to_string).collect();
+
+        format!("# Constant network\nlayers 1\n{} {}\n{}\n{}\n", ENCODING_LEN, NUM_OF_ACTIONS + 1, zeros, biases.join(" "))
+    }
+
+    #[test]
+    fn test_layer_forward() {
+        let layer = Layer::new(3, 2, vec![1.0, 0.0, -1.0, 0.5, 0.5, 0.5], vec![0.0, 1.0]).unwrap();
+
+        assert_eq!(layer.forward(&[1.0, 2.0, 3.0]), vec![-2.0, 4.0]);
+        assert!(Layer::new(3, 2, vec![1.0; 5], vec![0.0; 2]).is_err());
+        assert!(Layer::new(3, 2, vec![1.0; 6], vec![0.0; 3]).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_layer_forward_wrong_input() {
+        Layer::new(3, 2, vec![1.0; 6], vec![0.0; 2]).unwrap().forward(&[1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_new() {
+        let layer = |inputs: usize, outputs: usize| Layer::new(inputs, outputs, vec![0.0; inputs * outputs], vec![0.0; outputs]).unwrap();
+
+        assert!(MlpEvaluator::new(vec![layer(ENCODING_LEN, 8), layer(8, NUM_OF_ACTIONS + 1)]).is_ok());
+        assert!(MlpEvaluator::new(vec![]).is_err());
+        assert!(MlpEvaluator::new(vec![layer(ENCODING_LEN - 1, NUM_OF_ACTIONS + 1)]).is_err());
+        assert!(MlpEvaluator::new(vec![layer(ENCODING_LEN, NUM_OF_ACTIONS)]).is_err());
+        assert!(MlpEvaluator::new(vec![layer(ENCODING_LEN, 8), layer(9, NUM_OF_ACTIONS + 1)]).is_err());
+    }
+
+    #[test]
+    fn test_forward() {
+        // Smaller than a real network, which `new` would refuse.
+        let network = MlpEvaluator {
+            layers: vec![
+                Layer::new(2, 2, vec![1.0, 0.0, 0.0, 1.0], vec![0.0, 0.0]).unwrap(),
+                Layer::new(2, 1, vec![1.0, 1.0], vec![0.5]).unwrap(),
+            ],
+        };
+
+        // The hidden layer goes through ReLU, the output layer does not.
+        assert_eq!(network.forward(&[2.0, -3.0]), vec![2.5]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut biases = vec![0.0; NUM_OF_ACTIONS + 1];
+        biases[action_index(2, Stone::build(4))] = 5.0;
+        biases[NUM_OF_ACTIONS] = 0.5;
+
+        let network = MlpEvaluator::parse(&constant_network(&biases)).unwrap();
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+        let evaluation = network.evaluate(&game.observation(game.to_move()));
+
+        assert!((evaluation.value - 0.5f32.tanh()).abs() < 1e-6);
+        assert!((evaluation.priors.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        assert_eq!(
+            evaluation.priors.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0,
+            action_index(2, Stone::build(4)),
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(MlpEvaluator::parse("").is_err());
+        assert!(MlpEvaluator::parse("layers 1\n2 2\n1 2 3 4\n0 0").is_err());
+        assert!(MlpEvaluator::parse(&format!("{} 1", constant_network(&[0.0; NUM_OF_ACTIONS + 1]))).is_err());
+        assert!(MlpEvaluator::parse(&constant_network(&[0.0; NUM_OF_ACTIONS + 1]).replace("layers 1", "layers 2")).is_err());
+        assert!(MlpEvaluator::parse(&constant_network(&[0.0; NUM_OF_ACTIONS + 1]).replacen(" 0", " x", 1)).is_err());
+    }
+}
+
+pub struct Layer {
+    inputs: usize,
+    outputs: usize,
+    weights: Vec<f32>,
+    biases: Vec<f32>,
+}
+impl Layer {
+    pub fn new(inputs: usize, outputs: usize, weights: Vec<f32>, biases: Vec<f32>) -> Result<Self, String> {
+        if weights.len() != inputs * outputs {
+            return Err(format!("Expected {} weights, got {}", inputs * outputs, weights.len()));
+        }
+        if biases.len() != outputs {
+            return Err(format!("Expected {} biases, got {}", outputs, biases.len()));
+        }
+
+        Ok(Layer { inputs, outputs, weights, biases })
+    }
+
+    // Panics if `input` does not have as many values as the layer has inputs.
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        assert_eq!(input.len(), self.inputs, "The input should have as many values as the layer has inputs.");
+
+        self.weights
+            .chunks_exact(self.inputs)
+            .zip(self.biases.iter())
+            .map(|(row, bias)| row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>() + bias)
+            .collect()
+    }
+}
+
+pub struct MlpEvaluator {
+    layers: Vec<Layer>,
+}
+impl MlpEvaluator {
+    // Layers should map ENCODING_LEN inputs to NUM_OF_ACTIONS + 1 outputs, see the top of this file.
+    pub fn new(layers: Vec<Layer>) -> Result<Self, String> {
+        if layers.first().map(|layer| layer.inputs) != Some(ENCODING_LEN) {
+            return Err(format!("The first layer should have {ENCODING_LEN} inputs"));
+        }
+        if layers.last().map(|layer| layer.outputs) != Some(NUM_OF_ACTIONS + 1) {
+            return Err(format!("The last layer should have {} outputs", NUM_OF_ACTIONS + 1));
+        }
+        if layers.windows(2).any(|pair| pair[0].outputs != pair[1].inputs) {
+            return Err(String::from("Consecutive layers should fit each other"));
+        }
+
+        Ok(MlpEvaluator { layers })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref()).map_err(|e| format!("{}: {e}", path.as_ref().display()))?;
+
+        Self::parse(&text)
+    }
+
+    // Parses a weights file, see the top of this file for the format.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut tokens = text.lines().flat_map(|line| line.split('#').next().unwrap_or("").split_whitespace());
+        let mut next = |what: &str| tokens.next().ok_or_else(|| format!("Missing {what}"));
+
+        if next("header")? != "layers" {
+            return Err(String::from("Expected 'layers'"));
+        }
+
+        let number: usize = next("number of layers")?.parse().map_err(|e| format!("Invalid number of layers: {e}"))?;
+        let mut layers = Vec::new();
+
+        for i in 0..number {
+            let inputs: usize = next("layer inputs")?.parse().map_err(|e| format!("Invalid inputs of layer {i}: {e}"))?;
+            let outputs: usize = next("layer outputs")?.parse().map_err(|e| format!("Invalid outputs of layer {i}: {e}"))?;
+
+            let mut floats = |len: usize| -> Result<Vec<f32>, String> {
+                (0..len)
+                    .map(|_| next("weight")?.parse::<f32>().map_err(|e| format!("Invalid weight in layer {i}: {e}")))
+                    .collect()
+            };
+
+            let weights = floats(inputs * outputs)?;
+            let biases = floats(outputs)?;
+
+            layers.push(Layer::new(inputs, outputs, weights, biases)?);
+        }
+
+        if next("end").is_ok() {
+            return Err(String::from("Unexpected data after the last layer"));
+        }
+
+        Self::new(layers)
+    }
+
+    pub fn forward(&self, input: &[f32]) -> Vec<f32> {
+        let mut activations = input.to_vec();
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            activations = layer.forward(&activations);
+
+            if i + 1 < self.layers.len() {
+                activations.iter_mut().for_each(|x| *x = x.max(0.0));
+            }
+        }
+
+        activations
+    }
+}
+impl Evaluator for MlpEvaluator {
+    fn evaluate(&self, observation: &Observation) -> Evaluation {
+        let output = self.forward(&encode(observation));
+        let mask = legal_action_mask(observation);
+
+        // Softmax over the legal actions.
+        let max = output[..NUM_OF_ACTIONS]
+            .iter()
+            .zip(&mask)
+            .filter(|(_, m)| **m > 0.0)
+            .map(|(x, _)| *x)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let exps: Vec<f32> = output[..NUM_OF_ACTIONS].iter().map(|x| (x - max).exp()).collect();
+
+        Evaluation { priors: normalized_priors(&exps, &mask), value: output[NUM_OF_ACTIONS].tanh() }
+    }
+}
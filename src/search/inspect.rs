@@ -0,0 +1,237 @@
+// Looking inside a search, to see why a move was chosen. Search players keep the result of their last decision
+// (e.g. `UctPlayer::last_search`), which prints as a table of the root children:
+//
+// Move      Visits       %   Value   Prior  Principal variation
+// Re7 3        412    41.2   0.631       -  Re7 3, Bl2 5, Re8 3
+// Pu1 9        208    20.8   0.577       -  Pu1 9, Bl2 5
+//
+// and exports the top of the tree to Graphviz with `to_dot`, e.g. for `dot -Tsvg search.dot > search.svg`.
+
+use std::fmt::{self, Write as _};
+
+use super::{ChildStats, SearchAction, SearchResult};
+use crate::common::NUM_OF_STONES;
+use crate::components::Stone;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::components::{Card, Player};
+    use crate::search::uct::{search, UctConfig};
+    use crate::search::KEPT_DEPTH;
+    use crate::Game;
+
+    fn example() -> SearchResult {
+        let mut first = ChildStats::new(Some((Card::build(7, 3), Stone::build(2))), 30, 20.0);
+        let mut reply = ChildStats::new(Some((Card::build(2, 6), Stone::build(4))), 20, 5.0);
+
+        reply.children.push(ChildStats::new(Some((Card::build(8, 3), Stone::build(2))), 12, 9.0));
+        first.children.push(ChildStats::new(None, 9, 4.0));
+        first.children.push(reply);
+
+        let mut second = ChildStats::new(Some((Card::build(1, 1), Stone::build(8))), 10, 3.0);
+        second.prior = Some(0.25);
+
+        SearchResult { player: Player::build(0), children: vec![second, first] }
+    }
+
+    #[test]
+    fn test_principal_variation() {
+        let result = example();
+
+        assert_eq!(
+            result.principal_variation(),
+            vec![
+                Some((Card::build(7, 3), Stone::build(2))),
+                Some((Card::build(2, 6), Stone::build(4))),
+                Some((Card::build(8, 3), Stone::build(2))),
+            ]
+        );
+        assert_eq!(action_notation(&None), "pass");
+        assert_eq!(action_notation(&result.principal_variation()[0]), "Re7 3");
+    }
+
+    #[test]
+    fn test_table() {
+        let table = example().to_string();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Move"));
+        assert!(lines[1].starts_with("Re7 3"));
+        assert!(lines[1].contains("75.0"));
+        assert!(lines[1].contains("0.667"));
+        assert!(lines[1].ends_with("Re7 3, Bl2 5, Re8 3"));
+        assert!(lines[2].contains("0.250"));
+    }
+
+    #[test]
+    fn test_action_notation() {
+        for action in [Some((Card::build(7, 3), Stone::build(2))), Some((Card::build(1, 6), Stone::build(8))), None] {
+            assert_eq!(parse_action(&action_notation(&action)).unwrap(), action);
+        }
+
+        assert!(parse_action("Re7 0").is_err());
+        assert!(parse_action("Re7 10").is_err());
+        assert!(parse_action("Re0 3").is_err());
+        assert!(parse_action("Re7").is_err());
+    }
+
+    #[test]
+    fn test_dot() {
+        let dot = example().to_dot(2);
+
+        assert!(dot.starts_with("digraph search {"));
+        assert!(dot.trim_end().ends_with('}'));
+        // The root, two children and two grandchildren, cut below.
+        assert_eq!(dot.matches("label=").count(), 5);
+        assert_eq!(dot.matches("->").count(), 4);
+        assert!(!dot.contains("Re8 3"));
+        assert_eq!(dot.matches("penwidth").count(), 2);
+    }
+
+    #[test]
+    fn test_search_keeps_tree() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(0));
+        let config = UctConfig { iterations: 300, determinizations: 2, ..UctConfig::default() };
+
+        let result = search(&game.observation(game.to_move()), &config, &mut StdRng::seed_from_u64(1));
+        let variation = result.principal_variation();
+
+        assert!(variation.len() >= 2 && variation.len() <= KEPT_DEPTH);
+        assert_eq!(variation[0], result.best().action);
+
+        fn depth(child: &ChildStats) -> usize {
+            1 + child.children.iter().map(depth).max().unwrap_or(0)
+        }
+
+        assert!(result.children.iter().all(|child| depth(child) <= KEPT_DEPTH));
+    }
+}
+
+// A move as printed in tables, e.g. "Re7 3" for 7 of Red on the third stone.
+pub fn action_notation(action: &SearchAction) -> String {
+    match action {
+        Some((card, stone)) => format!("{} {}", card, stone.get_stone()),
+        None => String::from("pass"),
+    }
+}
+
+// Parses the notation of `action_notation`.
+pub fn parse_action(notation: &str) -> Result<SearchAction, String> {
+    let notation = notation.trim();
+
+    if notation == "pass" { return Ok(None); }
+
+    let invalid = || format!("Invalid action: {notation}");
+    let (card, stone) = notation.split_once(' ').ok_or_else(invalid)?;
+
+    match stone.trim().parse::<u8>() {
+        Ok(stone) if (1..=NUM_OF_STONES).contains(&stone) => Ok(Some((card.parse()?, Stone::build(stone - 1)))),
+        _ => Err(invalid()),
+    }
+}
+
+fn most_visited(children: &[ChildStats]) -> Option<&ChildStats> {
+    children.iter().max_by_key(|child| child.visits)
+}
+
+// The line of play through `child`, following the most visited action at every level kept.
+fn variation_from(child: &ChildStats) -> Vec<SearchAction> {
+    let mut variation = vec![child.action.clone()];
+    let mut current = child;
+
+    while let Some(next) = most_visited(&current.children) {
+        variation.push(next.action.clone());
+        current = next;
+    }
+
+    variation
+}
+
+impl SearchResult {
+    // The line of play the search expects, as deep as the kept tree goes (see `KEPT_DEPTH`).
+    pub fn principal_variation(&self) -> Vec<SearchAction> {
+        most_visited(&self.children).map(variation_from).unwrap_or_default()
+    }
+
+    // The top `depth` plies of the tree in Graphviz DOT. Edges of the principal variation are drawn bold.
+    pub fn to_dot(&self, depth: usize) -> String {
+        let mut dot = String::from("digraph search {\n    node [shape=box, fontname=\"monospace\"];\n");
+        let visits: u32 = self.children.iter().map(|child| child.visits).sum();
+
+        writeln!(dot, "    n0 [label=\"Player {} to move\\n{} visits\"];", self.player.get_player(), visits)
+            .expect("Writing to a String cannot fail.");
+
+        let mut next_id = 1;
+        write_dot_children(&mut dot, 0, &self.children, depth, &mut next_id);
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn write_dot_children(dot: &mut String, parent: usize, children: &[ChildStats], depth: usize, next_id: &mut usize) {
+    if depth == 0 { return; }
+
+    let best = most_visited(children).map(|child| child.action.clone());
+
+    for child in children.iter() {
+        let id = *next_id;
+        *next_id += 1;
+
+        let prior = child.prior.map(|prior| format!("\\nprior {:.3}", prior)).unwrap_or_default();
+        let style = if Some(&child.action) == best.as_ref() { " [penwidth=2]" } else { "" };
+
+        writeln!(
+            dot,
+            "    n{} [label=\"{}\\n{} visits\\nvalue {:.3}{}\"];\n    n{} -> n{}{};",
+            id,
+            action_notation(&child.action),
+            child.visits,
+            child.mean_value(),
+            prior,
+            parent,
+            id,
+            style,
+        )
+        .expect("Writing to a String cannot fail.");
+
+        write_dot_children(dot, id, &child.children, depth - 1, next_id);
+    }
+}
+
+// Root children by decreasing visits. Values are mean rewards of the searching player.
+impl fmt::Display for SearchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total: u32 = self.children.iter().map(|child| child.visits).sum();
+        let mut children: Vec<&ChildStats> = self.children.iter().collect();
+
+        children.sort_by_key(|child| std::cmp::Reverse(child.visits));
+
+        write!(f, "{:<8}{:>8}{:>8}{:>8}{:>8}  Principal variation", "Move", "Visits", "%", "Value", "Prior")?;
+
+        for child in children {
+            let share = if total == 0 { 0.0 } else { 100.0 * child.visits as f64 / total as f64 };
+            let prior = child.prior.map_or(String::from("-"), |prior| format!("{:.3}", prior));
+            let variation: Vec<String> = variation_from(child).iter().map(action_notation).collect();
+
+            write!(
+                f,
+                "\n{:<8}{:>8}{:>8.1}{:>8.3}{:>8}  {}",
+                action_notation(&child.action),
+                child.visits,
+                share,
+                child.mean_value(),
+                prior,
+                variation.join(", "),
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::Instant;
+
+use rand::Rng;
+
+use super::determinize::determinize;
+use super::uct::UctConfig;
+use super::{actions, apply, begin_turn, simulate, solve_endgame, Budget, ChildStats, SearchAction, SearchResult, TreeStats};
+use crate::components::Player;
+use crate::observation::Observation;
+use crate::Game;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_visits_add_up() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let game = Game::with_rng(&mut rng);
+        let config = UctConfig { iterations: 300, ..UctConfig::default() };
+
+        let result = search(&game.observation(game.to_move()), &config, &mut rng);
+
+        assert_eq!(result.player, game.to_move());
+        assert_eq!(result.children.iter().map(|child| child.visits).sum::<u32>(), 300);
+        assert_eq!(result.children.len(), game.legal_actions().len());
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(1));
+        let config = UctConfig { iterations: 100, ..UctConfig::default() };
+        let observation = game.observation(game.to_move());
+
+        let result1 = search(&observation, &config, &mut StdRng::seed_from_u64(2));
+        let result2 = search(&observation, &config, &mut StdRng::seed_from_u64(2));
+
+        assert_eq!(format!("{:?}", result1), format!("{:?}", result2));
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let game = Game::with_rng(&mut rng);
+        let config = UctConfig { iterations: u32::MAX, time_limit: Some(Duration::from_millis(50)), ..UctConfig::default() };
+
+        let start = Instant::now();
+        let result = search(&game.observation(game.to_move()), &config, &mut rng);
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(result.children.iter().map(|child| child.visits).sum::<u32>() > 0);
+    }
+}
+
+// Single observer information set MCTS: one tree over the information sets of the searching player,
+// with a new determinization sampled for every iteration. Children are only selectable in
+// determinizations where their action is legal, and their exploration term counts how often they were available.
+// Uses `iterations`, `exploration`, `rollout`, `endgame_nodes` and `time_limit` from `config`,
+// and searches a single tree on the calling thread whatever `determinizations`, `threads` and `parallelism`.
+pub fn search<R: Rng + ?Sized>(observation: &Observation, config: &UctConfig, rng: &mut R) -> SearchResult {
+    let start = Instant::now();
+
+    if let Some(result) = solve_endgame(observation, config.endgame_nodes, config.time_limit, start) {
+        return result;
+    }
+
+    let mut tree = Tree::new(observation.player);
+    let budget = Budget::new(config.iterations, config.time_limit.map(|limit| start + limit), 1);
+    let mut done = 0;
+
+    while budget.proceed(done, || tree.root_visits()) {
+        let game = determinize(observation, rng);
+
+        tree.iterate(game, config, rng);
+        done += 1;
+    }
+
+    tree.root_stats()
+}
+
+struct Node {
+    action: SearchAction,
+    // The player who made the action leading to this node.
+    mover: Player,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    visits: u32,
+    // Number of times the node was selectable from its parent.
+    availability: u32,
+    // Sum of the rewards of `mover`.
+    value: f64,
+}
+
+struct Tree {
+    nodes: Vec<Node>,
+}
+impl Tree {
+    fn new(player: Player) -> Self {
+        let root = Node {
+            action: None,
+            mover: player.get_other(),
+            parent: None,
+            children: Vec::new(),
+            visits: 0,
+            availability: 0,
+            value: 0.0,
+        };
+
+        Tree { nodes: vec![root] }
+    }
+
+    fn ucb(&self, child: usize, exploration: f64) -> f64 {
+        let child = &self.nodes[child];
+
+        child.value / child.visits as f64 + exploration * ((child.availability as f64).ln() / child.visits as f64).sqrt()
+    }
+
+    fn iterate<R: Rng + ?Sized>(&mut self, mut game: Game, config: &UctConfig, rng: &mut R) {
+        let mut node = 0;
+
+        // Selection and expansion
+        while game.winner().is_none() {
+            let legal = actions(&game);
+            let compatible: Vec<usize> = self.nodes[node]
+                .children
+                .iter()
+                .copied()
+                .filter(|child| legal.contains(&self.nodes[*child].action))
+                .collect();
+
+            for child in compatible.iter() {
+                self.nodes[*child].availability += 1;
+            }
+
+            let untried: Vec<SearchAction> = legal
+                .into_iter()
+                .filter(|action| compatible.iter().all(|child| self.nodes[*child].action != *action))
+                .collect();
+
+            if !untried.is_empty() {
+                let action = untried[rng.gen_range(0..untried.len())].clone();
+
+                node = self.expand(node, &game, action.clone());
+
+                apply(&mut game, &action);
+                begin_turn(&mut game);
+                break;
+            }
+
+            node = *compatible
+                .iter()
+                .max_by(|a, b| self.ucb(**a, config.exploration).total_cmp(&self.ucb(**b, config.exploration)))
+                .expect("A node without untried actions should have compatible children.");
+
+            apply(&mut game, &self.nodes[node].action);
+            begin_turn(&mut game);
+        }
+
+        // Simulation
+        let winner = match game.winner() {
+            Some(winner) => winner,
+            None => simulate(game, config.rollout.as_ref(), rng),
+        };
+
+        // Backpropagation
+        let mut current = Some(node);
+
+        while let Some(n) = current {
+            let node = &mut self.nodes[n];
+
+            node.visits += 1;
+            if node.mover == winner { node.value += 1.0; }
+
+            current = node.parent;
+        }
+    }
+
+    fn expand(&mut self, parent: usize, game: &Game, action: SearchAction) -> usize {
+        self.nodes.push(Node {
+            action,
+            mover: game.to_move(),
+            parent: Some(parent),
+            children: Vec::new(),
+            visits: 0,
+            availability: 1,
+            value: 0.0,
+        });
+
+        let index = self.nodes.len() - 1;
+        self.nodes[parent].children.push(index);
+
+        index
+    }
+}
+impl TreeStats for Tree {
+    fn root_player(&self) -> Player { self.nodes[0].mover.get_other() }
+
+    fn edges(&self, node: usize) -> Vec<(&SearchAction, usize)> {
+        self.nodes[node].children.iter().map(|child| (&self.nodes[*child].action, *child)).collect()
+    }
+
+    fn node_stats(&self, action: &SearchAction, node: usize) -> ChildStats {
+        ChildStats::new(action.clone(), self.nodes[node].visits, self.nodes[node].value)
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::RngCore;
+
+use super::rollout::greedy_score;
+use crate::board::cards_on_board::CardsOnBoard;
+use crate::common::{CARDS_IN_DECK, NUM_OF_COLORS, NUM_OF_NUMS};
+use crate::components::{Card, Player, Stone};
+use crate::encode::card_index;
+use crate::observation::Observation;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::board::hand::Hand;
+
+    #[test]
+    fn test_bayesian_model() {
+        // The opponent had 7-8 of Red on stone 1 and played a 1 of Purple elsewhere:
+        // they are unlikely to hold 6 or 9 of Red, which would have completed a color run.
+        let opponent = Player::build(1);
+        let mut board = CardsOnBoard::new();
+
+        board.place_card(opponent, Stone::build(0), Card::build(7, 3));
+        board.place_card(opponent, Stone::build(0), Card::build(8, 3));
+
+        let mut model = BayesianModel::default();
+        model.observe(&board, opponent, &Card::build(1, 1), Stone::build(4));
+
+        assert!(model.card_weight(&Card::build(9, 3)) < model.card_weight(&Card::build(2, 2)));
+        assert!(model.card_weight(&Card::build(6, 3)) < model.card_weight(&Card::build(2, 2)));
+        assert_eq!(model.card_weight(&Card::build(2, 2)), 1.0);
+
+        model.reset();
+
+        assert_eq!(model.card_weight(&Card::build(9, 3)), 1.0);
+    }
+
+    #[test]
+    fn test_frequency_model() {
+        let opponent = Player::build(1);
+        let board = CardsOnBoard::new();
+
+        let mut model = FrequencyModel::default();
+        model.observe(&board, opponent, &Card::build(4, 5), Stone::build(2));
+        model.observe(&board, opponent, &Card::build(6, 5), Stone::build(3));
+
+        assert!(model.card_weight(&Card::build(1, 5)) > model.card_weight(&Card::build(1, 4)));
+        assert!(model.card_weight(&Card::build(4, 1)) > model.card_weight(&Card::build(3, 1)));
+    }
+
+    #[test]
+    fn test_sample_hands() {
+        let mut hand = Hand::new();
+        for num in 1..=6 { hand.add(Card::build(num, 1)); }
+
+        let board = CardsOnBoard::new();
+        let observation = Observation::new(&hand, &board, Player::build(0));
+        let mut model = FrequencyModel::default();
+
+        model.observe(&board, Player::build(1), &Card::build(9, 2), Stone::build(0));
+
+        let samples = model.sample_hands(&observation, 20, &mut StdRng::seed_from_u64(0));
+        let unseen = observation.unseen_cards();
+
+        assert_eq!(samples.len(), 20);
+
+        for sample in samples.iter() {
+            assert_eq!(sample.cards.len(), observation.opponent_hand_len());
+            assert!(sample.cards.iter().all(|card| unseen.contains(card)));
+            assert!(sample.cards.iter().all_unique());
+
+            let product: f64 = sample.cards.iter().map(|card| model.card_weight(card)).product();
+
+            assert!((sample.weight - product).abs() < 1e-9);
+        }
+    }
+}
+
+// A possible hand of the opponent with its relative likelihood.
+#[derive(Clone, Debug)]
+pub struct WeightedHand {
+    pub cards: Vec<Card>,
+    pub weight: f64,
+}
+
+// Beliefs about the hidden hand of the opponent, updated from their placements.
+// Cards are treated independently: each card has a weight, relative to 1 for a card nothing is known about.
+pub trait OpponentModel: fmt::Debug + Send + Sync {
+    // Updates the beliefs after `opponent` placed `card` on `stone`. `board` is the board before the placement.
+    fn observe(&mut self, board: &CardsOnBoard, opponent: Player, card: &Card, stone: Stone);
+
+    // Relative likelihood that `card` is in the opponent's hand, if it is unseen.
+    fn card_weight(&self, card: &Card) -> f64;
+
+    // Forgets everything, e.g. before a new game.
+    fn reset(&mut self);
+
+    // Hands consistent with `observation`, drawn uniformly and weighted by the product of their card weights.
+    fn sample_hands(&self, observation: &Observation, samples: usize, rng: &mut dyn RngCore) -> Vec<WeightedHand> {
+        let mut unseen = observation.unseen_cards();
+        let len = observation.opponent_hand_len();
+
+        (0..samples)
+            .map(|_| {
+                let (cards, _) = unseen.partial_shuffle(rng, len);
+                let cards = cards.to_vec();
+                let weight = cards.iter().map(|card| self.card_weight(card)).product();
+
+                WeightedHand { cards, weight }
+            })
+            .collect()
+    }
+}
+
+fn all_cards() -> impl Iterator<Item = Card> {
+    (1..=NUM_OF_NUMS).cartesian_product(1..=NUM_OF_COLORS).map(|(num, color)| Card::build(num, color))
+}
+
+// Assumes the opponent plays roughly like `GreedyRollout`: every card that would have made a better formation
+// than the placement played becomes less likely to be in their hand, by a factor of `1 - evidence`.
+#[derive(Clone, Debug)]
+pub struct BayesianModel {
+    pub evidence: f64,
+    weights: Vec<f64>,
+}
+impl BayesianModel {
+    pub fn new(evidence: f64) -> Self {
+        BayesianModel { evidence: evidence.clamp(0.0, 1.0), weights: vec![1.0; CARDS_IN_DECK as usize] }
+    }
+}
+impl Default for BayesianModel {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+impl OpponentModel for BayesianModel {
+    fn observe(&mut self, board: &CardsOnBoard, opponent: Player, card: &Card, stone: Stone) {
+        let (played, _) = greedy_score(board, opponent, card, stone);
+        let stones = board.available_stones_for(opponent);
+
+        for other in all_cards().filter(|c| c != card && !board.is_present(c)) {
+            let better = stones.iter().any(|s| greedy_score(board, opponent, &other, *s).0 > played);
+
+            if better {
+                self.weights[card_index(&other)] *= 1.0 - self.evidence;
+            }
+        }
+    }
+
+    fn card_weight(&self, card: &Card) -> f64 {
+        self.weights[card_index(card)]
+    }
+
+    fn reset(&mut self) {
+        self.weights.iter_mut().for_each(|w| *w = 1.0);
+    }
+}
+
+// Assumes the opponent keeps cards matching what they play: each card gains `boost` per played card
+// of the same color, and per played card of the same number.
+#[derive(Clone, Debug)]
+pub struct FrequencyModel {
+    pub boost: f64,
+    colors: [u32; NUM_OF_COLORS as usize],
+    nums: [u32; NUM_OF_NUMS as usize],
+}
+impl FrequencyModel {
+    pub fn new(boost: f64) -> Self {
+        FrequencyModel { boost, colors: [0; NUM_OF_COLORS as usize], nums: [0; NUM_OF_NUMS as usize] }
+    }
+}
+impl Default for FrequencyModel {
+    fn default() -> Self {
+        Self::new(0.25)
+    }
+}
+impl OpponentModel for FrequencyModel {
+    fn observe(&mut self, _board: &CardsOnBoard, _opponent: Player, card: &Card, _stone: Stone) {
+        self.colors[card.color_index()] += 1;
+        self.nums[card.num_index()] += 1;
+    }
+
+    fn card_weight(&self, card: &Card) -> f64 {
+        1.0 + self.boost * (self.colors[card.color_index()] + self.nums[card.num_index()]) as f64
+    }
+
+    fn reset(&mut self) {
+        self.colors = [0; NUM_OF_COLORS as usize];
+        self.nums = [0; NUM_OF_NUMS as usize];
+    }
+}
@@ -0,0 +1,247 @@
+// Random playouts without heap allocation, the workhorse of simulations in search.
+//
+// A `Playout` is a compact copy of a game: cards are indices (see `encode::card_index`) in fixed-size arrays,
+// so copying one and playing it out never touches the heap. Moves are chosen like `UniformRollout` chooses them,
+// and claims are made as in `begin_turn`, so that a playout draws from a random number generator exactly as
+// `rollout` with `UniformRollout` does, and ends the same way.
+
+use rand::Rng;
+
+use crate::board::cards_on_board::stonecards::Formation;
+use crate::common::{CARDS_IN_DECK, CARDS_IN_HAND, NUM_OF_PLAYERS, NUM_OF_STONES, STONE_CARDS_LIMIT};
+use crate::components::{CardMask, Player, Stone};
+use crate::encode::{card_from_index, card_index};
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::search::{self, rollout::UniformRollout};
+
+    #[test]
+    fn test_matches_rollout() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for seed in 0..50 {
+            let mut game = Game::with_rng(&mut rng);
+
+            // Start some games midway, with claims made.
+            for _ in 0..seed % 40 {
+                if search::begin_turn(&mut game).is_some() { break; }
+
+                let action = search::actions(&game)[0].clone();
+                search::apply(&mut game, &action);
+            }
+
+            if game.winner().is_some() { continue; }
+
+            let playout = Playout::new(&game);
+            let winner = search::rollout(&mut game, &UniformRollout, &mut StdRng::seed_from_u64(seed));
+
+            assert_eq!(playout.play(&mut StdRng::seed_from_u64(seed)), winner);
+        }
+    }
+}
+
+// Up to N cards, as card indices.
+#[derive(Clone, Copy, Debug)]
+struct Cards<const N: usize> {
+    cards: [u8; N],
+    len: u8,
+}
+impl<const N: usize> Cards<N> {
+    fn new() -> Self {
+        Cards { cards: [0; N], len: 0 }
+    }
+
+    fn len(&self) -> usize { self.len as usize }
+
+    fn is_full(&self) -> bool { self.len() == N }
+
+    fn push(&mut self, card: u8) {
+        self.cards[self.len()] = card;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 { return None; }
+
+        self.len -= 1;
+
+        Some(self.cards[self.len()])
+    }
+
+    // Removes the card at `index`, replacing it by the last card, as `Hand::remove` does.
+    fn swap_remove(&mut self, index: usize) -> u8 {
+        let card = self.cards[index];
+
+        self.len -= 1;
+        self.cards[index] = self.cards[self.len()];
+
+        card
+    }
+}
+
+type Side = Cards<{ STONE_CARDS_LIMIT as usize }>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Playout {
+    // Cards on each side of each stone, by player and stone index.
+    sides: [[Side; NUM_OF_STONES as usize]; NUM_OF_PLAYERS as usize],
+    hands: [Cards<{ CARDS_IN_HAND as usize }>; NUM_OF_PLAYERS as usize],
+    // Cards are drawn from the end.
+    deck: Cards<{ CARDS_IN_DECK as usize }>,
+    // Cards not on the board, which may still complete a side.
+    unplayed: CardMask,
+    claims: [Option<Player>; NUM_OF_STONES as usize],
+    // The player who completed their side of each stone first.
+    advantage: [Option<Player>; NUM_OF_STONES as usize],
+    to_move: Player,
+}
+impl Playout {
+    pub fn new(game: &Game) -> Self {
+        let board = game.board();
+        let mut sides = [[Side::new(); NUM_OF_STONES as usize]; NUM_OF_PLAYERS as usize];
+        let mut hands = [Cards::new(); NUM_OF_PLAYERS as usize];
+        let mut deck = Cards::new();
+
+        for player in [Player::build(0), Player::build(1)] {
+            for stone in 0..NUM_OF_STONES {
+                for card in board.cards_of(player, Stone::build(stone)).iter() {
+                    sides[player.get_index()][stone as usize].push(card_index(card) as u8);
+                }
+            }
+
+            for card in game.hand(player).iter() {
+                hands[player.get_index()].push(card_index(card) as u8);
+            }
+        }
+
+        for i in 0..game.deck.len() {
+            deck.push(card_index(&game.deck[i]) as u8);
+        }
+
+        Playout {
+            sides,
+            hands,
+            deck,
+            unplayed: board.unplayed_cards(),
+            claims: std::array::from_fn(|stone| board.who_claimed(Stone::build(stone as u8))),
+            advantage: std::array::from_fn(|stone| board.advantage(Stone::build(stone as u8))),
+            to_move: game.to_move(),
+        }
+    }
+
+    // Plays uniformly random moves until the game ends, and returns the winner.
+    pub fn play<R: Rng + ?Sized>(mut self, rng: &mut R) -> Player {
+        loop {
+            if let Some(winner) = self.begin_turn() {
+                return winner;
+            }
+
+            let player = self.to_move;
+            let hand_len = self.hands[player.get_index()].len();
+            let stones = (0..NUM_OF_STONES as usize).filter(|stone| self.is_available(player, *stone));
+            let actions = stones.clone().count() * hand_len;
+
+            // Actions are ordered as in `Game::legal_actions`, by stone, then by hand index.
+            if actions > 0 {
+                let action = rng.gen_range(0..actions);
+                let stone = stones.clone().nth(action / hand_len).unwrap();
+
+                self.apply(action % hand_len, stone);
+            }
+
+            self.to_move = player.get_other();
+        }
+    }
+
+    // Claims every stone the player to move legally can. Returns the winner if the claims ended the game.
+    fn begin_turn(&mut self) -> Option<Player> {
+        for stone in 0..NUM_OF_STONES as usize {
+            if self.claims[stone].is_none() && self.is_legal_claim(self.to_move, stone) {
+                self.claims[stone] = Some(self.to_move);
+            }
+        }
+
+        self.winner()
+    }
+
+    fn is_available(&self, player: Player, stone: usize) -> bool {
+        self.claims[stone].is_none() && !self.sides[player.get_index()][stone].is_full()
+    }
+
+    fn apply(&mut self, hand_index: usize, stone: usize) {
+        let player = self.to_move;
+        let card = self.hands[player.get_index()].swap_remove(hand_index);
+        let side = &mut self.sides[player.get_index()][stone];
+
+        side.push(card);
+        self.unplayed.remove(&card_from_index(card as usize));
+
+        if side.is_full() && self.advantage[stone].is_none() {
+            self.advantage[stone] = Some(player);
+        }
+
+        if let Some(card) = self.deck.pop() {
+            self.hands[player.get_index()].push(card);
+        }
+    }
+
+    // As `CardsOnBoard::is_legal_claim`, on an unclaimed stone.
+    fn is_legal_claim(&self, player: Player, stone: usize) -> bool {
+        let mine = &self.sides[player.get_index()][stone];
+        let theirs = &self.sides[player.get_other().get_index()][stone];
+
+        if !mine.is_full() { return false; }
+
+        let strength = formation(mine.cards);
+
+        if theirs.is_full() {
+            let other = formation(theirs.cards);
+
+            return strength > other || (strength == other && self.advantage[stone] == Some(player));
+        }
+
+        // The player completed the stone first and wins ties, so only a stronger completion stops the claim.
+        !stronger_completion(*theirs, self.unplayed, strength)
+    }
+
+    // The player with three adjacent stones, or five stones.
+    fn winner(&self) -> Option<Player> {
+        [Player::build(0), Player::build(1)].into_iter().find(|player| {
+            let owns = |stone: usize| self.claims[stone] == Some(*player);
+
+            (0..NUM_OF_STONES as usize).filter(|stone| owns(*stone)).count() >= 5
+                || (0..NUM_OF_STONES as usize - 2).any(|stone| owns(stone) && owns(stone + 1) && owns(stone + 2))
+        })
+    }
+}
+
+fn formation(cards: [u8; STONE_CARDS_LIMIT as usize]) -> Formation {
+    let [first, second, third] = cards.map(|card| card_from_index(card as usize));
+
+    Formation::of([&first, &second, &third])
+}
+
+// Whether `side` can be completed with cards of `available` into a formation stronger than `strength`.
+fn stronger_completion(side: Side, available: CardMask, strength: Formation) -> bool {
+    if side.is_full() { return formation(side.cards) > strength; }
+
+    let mut rest = available;
+
+    // Later cards only go with later cards, so that each completion is tried once.
+    for card in available.iter() {
+        rest.remove(&card);
+
+        let mut side = side;
+        side.push(card_index(&card) as u8);
+
+        if stronger_completion(side, rest, strength) { return true; }
+    }
+
+    false
+}
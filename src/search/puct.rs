@@ -0,0 +1,303 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use super::determinize::determinize;
+use super::evaluator::Evaluator;
+use super::rollout::RolloutPolicy;
+use super::{apply, begin_turn, share, simulate, solve_endgame, Budget, ChildStats, SearchAction, SearchResult, TreeStats};
+use crate::components::Player;
+use crate::encode::action_index;
+use crate::observation::Observation;
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::components::Stone;
+    use crate::encode::NUM_OF_ACTIONS;
+    use crate::search::evaluator::{Evaluation, HeuristicEvaluator};
+    use crate::search::rollout::UniformRollout;
+
+    // Puts all the prior on one action, and values every position as even.
+    struct FixedEvaluator(usize);
+    impl Evaluator for FixedEvaluator {
+        fn evaluate(&self, _observation: &Observation) -> Evaluation {
+            let mut priors = vec![0.0; NUM_OF_ACTIONS];
+            priors[self.0] = 1.0;
+
+            Evaluation { priors, value: 0.0 }
+        }
+    }
+
+    #[test]
+    fn test_visits_add_up() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let game = Game::with_rng(&mut rng);
+        let config = PuctConfig { iterations: 200, determinizations: 4, ..PuctConfig::default() };
+
+        let result = search(&game.observation(game.to_move()), &config, &HeuristicEvaluator, &mut rng);
+
+        assert_eq!(result.player, game.to_move());
+        assert_eq!(result.children.len(), game.legal_actions().len());
+        assert_eq!(result.children.iter().map(|child| child.visits).sum::<u32>(), 200);
+        assert!(result.children.iter().all(|child| (0.0..=1.0).contains(&child.mean_value())));
+    }
+
+    #[test]
+    fn test_follows_priors() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let game = Game::with_rng(&mut rng);
+        let config = PuctConfig { iterations: 100, determinizations: 1, ..PuctConfig::default() };
+        let evaluator = FixedEvaluator(action_index(3, Stone::build(6)));
+
+        let result = search(&game.observation(game.to_move()), &config, &evaluator, &mut rng);
+
+        assert_eq!(result.best_action(game.hand(game.to_move())), Some((3, Stone::build(6))));
+        assert!(result.best().visits > 90);
+    }
+
+    #[test]
+    fn test_rollout() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let game = Game::with_rng(&mut rng);
+        let config = PuctConfig { iterations: 100, determinizations: 1, rollout: Some(Arc::new(UniformRollout)), ..PuctConfig::default() };
+
+        let result = search(&game.observation(game.to_move()), &config, &HeuristicEvaluator, &mut rng);
+
+        // Every leaf is valued by a won or lost simulation.
+        assert_eq!(result.children.iter().map(|child| child.visits).sum::<u32>(), 100);
+        assert!(result.children.iter().all(|child| child.value.fract() == 0.0));
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(2));
+        let config = PuctConfig { iterations: 100, determinizations: 2, ..PuctConfig::default() };
+        let observation = game.observation(game.to_move());
+
+        let result1 = search(&observation, &config, &HeuristicEvaluator, &mut StdRng::seed_from_u64(3));
+        let result2 = search(&observation, &config, &HeuristicEvaluator, &mut StdRng::seed_from_u64(3));
+
+        assert_eq!(format!("{:?}", result1), format!("{:?}", result2));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PuctConfig {
+    // Total iterations, split evenly between the determinizations.
+    pub iterations: u32,
+    pub determinizations: u32,
+    pub c_puct: f64,
+    // Values leaves by a simulation with this policy rather than by the evaluator, whose priors still guide the selection.
+    pub rollout: Option<Arc<dyn RolloutPolicy>>,
+    // See `UctConfig::endgame_nodes`.
+    pub endgame_nodes: u64,
+    // See `UctConfig::time_limit`.
+    pub time_limit: Option<Duration>,
+}
+impl Default for PuctConfig {
+    fn default() -> Self {
+        PuctConfig {
+            iterations: 800,
+            determinizations: 8,
+            c_puct: 1.5,
+            rollout: None,
+            endgame_nodes: 20000,
+            time_limit: None,
+        }
+    }
+}
+
+// AlphaZero style search on determinized games: instead of random rollouts,
+// leaves are valued by an `Evaluator`, whose priors also guide the selection.
+// The evaluator only sees the observation of the player to move at the leaf.
+pub fn search<E: Evaluator + ?Sized, R: Rng + ?Sized>(
+    observation: &Observation,
+    config: &PuctConfig,
+    evaluator: &E,
+    rng: &mut R,
+) -> SearchResult {
+    let start = Instant::now();
+
+    if let Some(result) = solve_endgame(observation, config.endgame_nodes, config.time_limit, start) {
+        return result;
+    }
+
+    let determinizations = config.determinizations.max(1);
+    let mut result = SearchResult { player: observation.player, children: Vec::new() };
+
+    for i in 0..determinizations {
+        let iterations = share(config.iterations, determinizations, i);
+        let deadline = config.time_limit.map(|limit| start + limit * (i + 1) / determinizations);
+
+        let game = determinize(observation, rng);
+        let mut tree = Tree::new(&game, observation.player, evaluator);
+        let budget = Budget::new(iterations, deadline, 1);
+        let mut done = 0;
+
+        while budget.proceed(done, || tree.root_visits()) {
+            tree.iterate(&game, config, evaluator, rng);
+            done += 1;
+        }
+
+        result.merge(&tree.root_stats());
+    }
+
+    result
+}
+
+struct Node {
+    action: SearchAction,
+    // The player who made the action leading to this node.
+    mover: Player,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    prior: f32,
+    expanded: bool,
+    winner: Option<Player>,
+    visits: u32,
+    // Sum of the rewards of `mover`, between 0 and 1 per visit.
+    value: f64,
+}
+
+// A PUCT tree over a single, fully known game.
+struct Tree {
+    nodes: Vec<Node>,
+}
+impl Tree {
+    fn new<E: Evaluator + ?Sized>(game: &Game, player: Player, evaluator: &E) -> Self {
+        let root = Node {
+            action: None,
+            mover: player.get_other(),
+            parent: None,
+            children: Vec::new(),
+            prior: 1.0,
+            expanded: false,
+            winner: game.winner(),
+            visits: 0,
+            value: 0.0,
+        };
+
+        let mut tree = Tree { nodes: vec![root] };
+
+        // The root is expanded up front, so that every iteration visits one of its children.
+        if tree.nodes[0].winner.is_none() {
+            tree.expand(0, game, &evaluator.evaluate(&game.observation(player)).priors);
+        }
+
+        tree
+    }
+
+    fn puct(&self, parent: usize, child: usize, c_puct: f64) -> f64 {
+        let child = &self.nodes[child];
+        // Counting at least one parent visit lets the priors decide the first selection.
+        let parent_visits = self.nodes[parent].visits.max(1) as f64;
+        let mean = if child.visits == 0 { 0.5 } else { child.value / child.visits as f64 };
+
+        mean + c_puct * child.prior as f64 * parent_visits.sqrt() / (1.0 + child.visits as f64)
+    }
+
+    fn iterate<E: Evaluator + ?Sized, R: Rng + ?Sized>(&mut self, root_game: &Game, config: &PuctConfig, evaluator: &E, rng: &mut R) {
+        let mut game = root_game.clone();
+        let mut node = 0;
+
+        // Selection
+        while self.nodes[node].expanded && self.nodes[node].winner.is_none() {
+            node = *self.nodes[node]
+                .children
+                .iter()
+                .max_by(|a, b| self.puct(node, **a, config.c_puct).total_cmp(&self.puct(node, **b, config.c_puct)))
+                .expect("An expanded node should have children.");
+
+            apply(&mut game, &self.nodes[node].action);
+
+            let winner = begin_turn(&mut game);
+
+            if !self.nodes[node].expanded { self.nodes[node].winner = winner; }
+        }
+
+        // Evaluation and expansion. `reward` is the reward of `player`.
+        let (player, reward) = match self.nodes[node].winner {
+            Some(winner) => (winner, 1.0),
+            None => {
+                let player = game.to_move();
+                let evaluation = evaluator.evaluate(&game.observation(player));
+
+                self.expand(node, &game, &evaluation.priors);
+
+                let reward = match &config.rollout {
+                    Some(policy) => f64::from(u8::from(simulate(game, policy.as_ref(), rng) == player)),
+                    None => (evaluation.value as f64 + 1.0) / 2.0,
+                };
+
+                (player, reward)
+            }
+        };
+
+        // Backpropagation
+        let mut current = Some(node);
+
+        while let Some(n) = current {
+            let node = &mut self.nodes[n];
+
+            node.visits += 1;
+            node.value += if node.mover == player { reward } else { 1.0 - reward };
+
+            current = node.parent;
+        }
+    }
+
+    fn expand(&mut self, parent: usize, game: &Game, priors: &[f32]) {
+        let mover = game.to_move();
+        let hand = game.hand(mover);
+        let legal_actions = game.legal_actions();
+
+        let mut children: Vec<(SearchAction, f32)> = legal_actions
+            .iter()
+            .map(|(hand_index, stone)| (Some((hand[*hand_index].clone(), *stone)), priors[action_index(*hand_index, *stone)].max(0.0)))
+            .collect();
+
+        if children.is_empty() {
+            children.push((None, 1.0));
+        }
+
+        // Renormalize over the legal actions, falling back to uniform priors.
+        let total: f32 = children.iter().map(|(_, prior)| prior).sum();
+        let uniform = 1.0 / children.len() as f32;
+
+        for (action, prior) in children {
+            self.nodes.push(Node {
+                action,
+                mover,
+                parent: Some(parent),
+                children: Vec::new(),
+                prior: if total > 0.0 { prior / total } else { uniform },
+                expanded: false,
+                winner: None,
+                visits: 0,
+                value: 0.0,
+            });
+
+            let index = self.nodes.len() - 1;
+            self.nodes[parent].children.push(index);
+        }
+
+        self.nodes[parent].expanded = true;
+    }
+}
+impl TreeStats for Tree {
+    fn root_player(&self) -> Player { self.nodes[0].mover.get_other() }
+
+    fn edges(&self, node: usize) -> Vec<(&SearchAction, usize)> {
+        self.nodes[node].children.iter().map(|child| (&self.nodes[*child].action, *child)).collect()
+    }
+
+    fn node_stats(&self, action: &SearchAction, node: usize) -> ChildStats {
+        ChildStats { prior: Some(self.nodes[node].prior), ..ChildStats::new(action.clone(), self.nodes[node].visits, self.nodes[node].value) }
+    }
+}
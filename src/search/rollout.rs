@@ -0,0 +1,335 @@
+use std::fmt;
+
+use rand::{Rng, RngCore};
+
+use crate::board::cards_on_board::stonecards::StoneCards;
+use crate::board::cards_on_board::CardsOnBoard;
+use crate::common::NUM_OF_STONES;
+use crate::components::{Card, CardMask, Player, Stone};
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::board::deck::Deck;
+    use crate::board::hand::Hand;
+    use crate::common::NUM_OF_COLORS;
+    use crate::search::rollout as play_out;
+
+    fn game_with(board: CardsOnBoard, cards: &[Card]) -> Game {
+        let mut hand1 = Hand::new();
+        for card in cards { hand1.add(card.clone()); }
+
+        Game { board, deck: Deck::from_cards(Vec::new()), hand1, hand2: Hand::new(), to_move: Player::build(0), auto_claim: Default::default() }
+    }
+
+    #[test]
+    fn test_formation_potential() {
+        let mut cards = StoneCards::new();
+
+        assert_eq!(formation_potential(&cards, &Card::build(4, 1)), 4);
+
+        cards.push(Card::build(4, 1));
+
+        assert_eq!(formation_potential(&cards, &Card::build(6, 1)), 4);
+        assert_eq!(formation_potential(&cards, &Card::build(4, 3)), 3);
+        assert_eq!(formation_potential(&cards, &Card::build(8, 1)), 2);
+        assert_eq!(formation_potential(&cards, &Card::build(5, 2)), 1);
+        assert_eq!(formation_potential(&cards, &Card::build(9, 2)), 0);
+    }
+
+    #[test]
+    fn test_greedy() {
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        board.place_card(player, Stone::build(3), Card::build(2, 5));
+        board.place_card(player, Stone::build(3), Card::build(3, 5));
+
+        let game = game_with(board, &[Card::build(9, 1), Card::build(4, 5), Card::build(6, 2)]);
+        let actions = game.legal_actions();
+
+        assert_eq!(GreedyRollout.choose(&game, &actions, &mut StdRng::seed_from_u64(0)), (1, Stone::build(3)));
+    }
+
+    #[test]
+    fn test_prune_lost_stones() {
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        // Stone 1 is lost, stone 2 is not since a Yellow color still beats the opponent's 1-2-3 run.
+        for num in 7..=9 { board.place_card(player.get_other(), Stone::build(0), Card::build(num, 4)); }
+        board.place_card(player, Stone::build(0), Card::build(1, 1));
+        for num in 1..=3 { board.place_card(player.get_other(), Stone::build(1), Card::build(num, num)); }
+        board.place_card(player, Stone::build(1), Card::build(1, 4));
+
+        let game = game_with(board, &[Card::build(5, 6)]);
+        let rules = PruningRules { lost_stones: true, color_runs: false };
+        let pruned = rules.prune(&game, &game.legal_actions());
+
+        assert!(!pruned.contains(&(0, Stone::build(0))));
+        assert!(pruned.contains(&(0, Stone::build(1))));
+        assert_eq!(pruned.len(), game.legal_actions().len() - 1);
+    }
+
+    #[test]
+    fn test_prune_lost_empty_stones() {
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        // Three 9s on stone 1, which only a color run beats. No color run is left once the 3s, 6s and 9s are played.
+        for color in 1..=3 { board.place_card(player.get_other(), Stone::build(0), Card::build(9, color)); }
+
+        let mut blockers: Vec<Card> = (1..=NUM_OF_COLORS).flat_map(|color| [Card::build(3, color), Card::build(6, color)]).collect();
+        blockers.extend((4..=NUM_OF_COLORS).map(|color| Card::build(9, color)));
+
+        for (i, card) in blockers.iter().enumerate().skip(1) {
+            board.place_card(Player::build((i % 2) as u8), Stone::build(1 + (i / 6) as u8), card.clone());
+        }
+
+        let rules = PruningRules { lost_stones: true, color_runs: false };
+
+        // 1-2-3 of Purple can still be made.
+        let game = game_with(board.clone(), &[Card::build(5, 6)]);
+        assert!(rules.prune(&game, &game.legal_actions()).contains(&(0, Stone::build(0))));
+
+        board.place_card(player.get_other(), Stone::build(4), blockers[0].clone());
+
+        let game = game_with(board, &[Card::build(5, 6)]);
+        assert!(!rules.prune(&game, &game.legal_actions()).contains(&(0, Stone::build(0))));
+    }
+
+    #[test]
+    fn test_prune_color_runs() {
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        board.place_card(player, Stone::build(5), Card::build(5, 2));
+        board.place_card(player, Stone::build(5), Card::build(6, 2));
+
+        // The other player holds 4 of Brown, but 7 of Brown may still come.
+        board.place_card(player.get_other(), Stone::build(2), Card::build(4, 2));
+
+        let game = game_with(board.clone(), &[Card::build(1, 1), Card::build(7, 2)]);
+        let rules = PruningRules { lost_stones: false, color_runs: true };
+        let pruned = rules.prune(&game, &game.legal_actions());
+
+        assert!(!pruned.contains(&(0, Stone::build(5))));
+        assert!(pruned.contains(&(1, Stone::build(5))));
+
+        // Once both completing cards are played elsewhere, nothing is left to break.
+        board.place_card(player.get_other(), Stone::build(2), Card::build(7, 2));
+
+        let game = game_with(board, &[Card::build(1, 1)]);
+
+        assert_eq!(rules.prune(&game, &game.legal_actions()).len(), game.legal_actions().len());
+    }
+
+    #[test]
+    fn test_prune_keeps_some_action() {
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        board.place_card(player, Stone::build(5), Card::build(5, 2));
+        board.place_card(player, Stone::build(5), Card::build(6, 2));
+
+        let game = game_with(board, &[Card::build(1, 1)]);
+        let actions = vec![(0, Stone::build(5))];
+
+        assert_eq!(PruningRules::default().prune(&game, &actions), actions);
+    }
+
+    #[test]
+    fn test_policies_end_games() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let policies: Vec<Box<dyn RolloutPolicy>> = vec![
+            Box::new(UniformRollout),
+            Box::new(GreedyRollout),
+            Box::new(EpsilonGreedyRollout { epsilon: 0.2 }),
+            Box::new(Pruned { policy: UniformRollout, rules: PruningRules::default() }),
+        ];
+
+        for policy in policies.iter() {
+            let mut game = Game::with_rng(&mut rng);
+            let winner = play_out(&mut game, policy.as_ref(), &mut rng);
+
+            assert_eq!(game.winner(), Some(winner));
+        }
+    }
+}
+
+// Chooses moves during the simulation phase of a search.
+// Implementations must be cheap, since they are called for every move of every simulation.
+pub trait RolloutPolicy: fmt::Debug + Send + Sync {
+    // Chooses one of `actions`, the legal actions of the player to move (never empty).
+    fn choose(&self, game: &Game, actions: &[(usize, Stone)], rng: &mut dyn RngCore) -> (usize, Stone);
+
+    // Whether every action is equally likely, as in a `Playout`, which then plays the simulations instead.
+    fn is_uniform(&self) -> bool { false }
+}
+
+// Optimistic category of the formation `cards` can still become with `card` added, ignoring which cards are left:
+// 4 for a color run, 3 for three of a kind, 2 for a color, 1 for a run, 0 for a sum.
+pub fn formation_potential(cards: &StoneCards, card: &Card) -> u8 {
+    let mut cards = cards.clone();
+    cards.push(card.clone());
+
+    cards.potential(&CardMask::all()).expect("Every card is available.") as u8
+}
+
+// Score of placing `card` on `stone` for `GreedyRollout`, higher is better.
+pub fn greedy_score(board: &CardsOnBoard, player: Player, card: &Card, stone: Stone) -> (usize, u8) {
+    let cards = board.cards_of(player, stone);
+
+    (formation_potential(cards, card) as usize * (cards.len() + 1), card.num())
+}
+
+#[derive(Debug)]
+pub struct UniformRollout;
+impl RolloutPolicy for UniformRollout {
+    fn choose(&self, _game: &Game, actions: &[(usize, Stone)], rng: &mut dyn RngCore) -> (usize, Stone) {
+        actions[rng.gen_range(0..actions.len())]
+    }
+
+    fn is_uniform(&self) -> bool { true }
+}
+
+// Plays the placement with the best `formation_potential`, weighted by the number of cards
+// the formation would hold, so that formations are completed rather than started everywhere.
+// Prefers higher cards, and breaks ties at random.
+#[derive(Debug)]
+pub struct GreedyRollout;
+impl RolloutPolicy for GreedyRollout {
+    fn choose(&self, game: &Game, actions: &[(usize, Stone)], rng: &mut dyn RngCore) -> (usize, Stone) {
+        let player = game.to_move();
+        let hand = game.hand(player);
+
+        let score = |(hand_index, stone): &(usize, Stone)| greedy_score(game.board(), player, &hand[*hand_index], *stone);
+
+        let best = actions.iter().map(score).max().expect("There should be a legal action.");
+        let best_actions: Vec<&(usize, Stone)> = actions.iter().filter(|action| score(action) == best).collect();
+
+        *best_actions[rng.gen_range(0..best_actions.len())]
+    }
+}
+
+// Plays a uniformly random move with probability `epsilon`, and the greedy move otherwise.
+#[derive(Debug)]
+pub struct EpsilonGreedyRollout {
+    pub epsilon: f64,
+}
+impl RolloutPolicy for EpsilonGreedyRollout {
+    fn choose(&self, game: &Game, actions: &[(usize, Stone)], rng: &mut dyn RngCore) -> (usize, Stone) {
+        if rng.gen_bool(self.epsilon.clamp(0.0, 1.0)) {
+            UniformRollout.choose(game, actions, rng)
+        } else {
+            GreedyRollout.choose(game, actions, rng)
+        }
+    }
+}
+
+// Rules removing obviously bad moves before a policy chooses.
+#[derive(Clone, Debug)]
+pub struct PruningRules {
+    // Avoid stones the player can no longer win, whatever cards come.
+    pub lost_stones: bool,
+    // Avoid breaking two cards of a color run while a card completing it is not played yet.
+    pub color_runs: bool,
+}
+impl Default for PruningRules {
+    fn default() -> Self {
+        PruningRules { lost_stones: true, color_runs: true }
+    }
+}
+impl PruningRules {
+    // Whether the player to move can no longer win `stone`.
+    // Only stones the other player completed are considered, so this never mistakes an open stone for a lost one.
+    fn is_lost(game: &Game, stone: Stone) -> bool {
+        let player = game.to_move();
+        let board = game.board();
+        let other_cards = board.cards_of(player.get_other(), stone);
+
+        if !other_cards.is_full() { return false; }
+
+        let cards = board.cards_of(player, stone);
+
+        if cards.is_empty() {
+            // The other player completed the stone first, so they win a tie: the stone is lost unless a completion
+            // is stronger. Categories settle most stones without going through the completions.
+            let strength = other_cards.strength();
+            let available = board.unplayed_cards();
+
+            match cards.potential(&available) {
+                Some(category) if category > strength.category() => false,
+                Some(category) if category == strength.category() => !cards.any_completion(&available, |full| full.strength() > strength),
+                _ => true,
+            }
+        } else {
+            board.is_legal_claim(player.get_other(), stone)
+        }
+    }
+
+    fn breaks_color_run(game: &Game, (hand_index, stone): &(usize, Stone)) -> bool {
+        let player = game.to_move();
+        let board = game.board();
+        let cards = board.cards_of(player, *stone);
+
+        if cards.len() != 2 { return false; }
+
+        let (low, high) = if cards[0].num() < cards[1].num() { (&cards[0], &cards[1]) } else { (&cards[1], &cards[0]) };
+
+        if low.color() != high.color() || high.num() - low.num() > 2 || high.num() == low.num() {
+            return false;
+        }
+
+        // Cards completing the run: the one in the middle, or either end.
+        let completions: Vec<Card> = if high.num() - low.num() == 2 {
+            vec![Card::build(low.num() + 1, low.color())]
+        } else {
+            [low.num().checked_sub(1).filter(|num| *num >= 1), Some(high.num() + 1).filter(|num| *num <= 9)]
+                .into_iter()
+                .flatten()
+                .map(|num| Card::build(num, low.color()))
+                .collect()
+        };
+
+        let completable = completions.iter().any(|card| !board.is_present(card));
+
+        completable && !completions.contains(&game.hand(player)[*hand_index])
+    }
+
+    // Keeps the actions not pruned by any rule, or every action if all of them would be pruned.
+    pub fn prune(&self, game: &Game, actions: &[(usize, Stone)]) -> Vec<(usize, Stone)> {
+        let mut lost = [false; NUM_OF_STONES as usize];
+
+        if self.lost_stones {
+            for stone in game.board().available_stones_for(game.to_move()) {
+                lost[stone.get_index()] = Self::is_lost(game, stone);
+            }
+        }
+
+        let kept: Vec<(usize, Stone)> = actions
+            .iter()
+            .filter(|action| !lost[action.1.get_index()])
+            .filter(|action| !(self.color_runs && Self::breaks_color_run(game, action)))
+            .copied()
+            .collect();
+
+        if kept.is_empty() { actions.to_vec() } else { kept }
+    }
+}
+
+// Applies `rules` before letting `policy` choose.
+#[derive(Debug)]
+pub struct Pruned<P: RolloutPolicy> {
+    pub policy: P,
+    pub rules: PruningRules,
+}
+impl<P: RolloutPolicy> RolloutPolicy for Pruned<P> {
+    fn choose(&self, game: &Game, actions: &[(usize, Stone)], rng: &mut dyn RngCore) -> (usize, Stone) {
+        self.policy.choose(game, &self.rules.prune(game, actions), rng)
+    }
+}
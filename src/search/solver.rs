@@ -0,0 +1,269 @@
+use std::time::Instant;
+
+use super::determinize::reveal;
+use super::rollout::greedy_score;
+use super::transposition::TranspositionTable;
+use super::{actions, apply, begin_turn, ChildStats, SearchAction, SearchResult};
+use crate::components::Player;
+use crate::observation::Observation;
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::search::uct::UctConfig;
+
+    // Plays random moves until the deck is empty and at most `cards` cards are left in the hands.
+    fn random_endgame(rng: &mut StdRng, cards: usize) -> Game {
+        loop {
+            let mut game = Game::with_rng(rng);
+
+            loop {
+                if begin_turn(&mut game).is_some() { break; }
+
+                if game.deck_len() == 0 && game.hand(Player::build(0)).len() + game.hand(Player::build(1)).len() <= cards {
+                    return game;
+                }
+
+                let actions = game.legal_actions();
+
+                if actions.is_empty() {
+                    game.pass();
+                } else {
+                    game.apply_action(actions[rng.gen_range(0..actions.len())]);
+                }
+            }
+        }
+    }
+
+    // Plain minimax without pruning or transpositions.
+    fn reference_winner(game: &Game) -> Player {
+        let me = game.to_move();
+
+        for action in actions(game) {
+            let mut child = game.clone();
+            apply(&mut child, &action);
+
+            let winner = begin_turn(&mut child).unwrap_or_else(|| reference_winner(&child));
+
+            if winner == me { return me; }
+        }
+
+        me.get_other()
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..30 {
+            let game = random_endgame(&mut rng, 5);
+            let solution = solve(&game, u64::MAX).unwrap();
+
+            assert_eq!(solution.winner, reference_winner(&game));
+
+            // The best move keeps the solved outcome.
+            if solution.best_action.is_some() {
+                let mut child = game.clone();
+                apply(&mut child, &solution.best_action);
+
+                let winner = begin_turn(&mut child).unwrap_or_else(|| solve(&child, u64::MAX).unwrap().winner);
+
+                assert_eq!(winner, solution.winner);
+            }
+        }
+    }
+
+    #[test]
+    fn test_node_limit() {
+        let game = random_endgame(&mut StdRng::seed_from_u64(1), 12);
+
+        assert!(solve(&game, 10).is_none());
+    }
+
+    #[test]
+    fn test_node_count() {
+        let game = random_endgame(&mut StdRng::seed_from_u64(1), 12);
+        let nodes = solve(&game, u64::MAX).unwrap().nodes;
+
+        // A table sized from the node limit keeps enough solved positions for this endgame
+        // to fit in the default limit of the search players.
+        assert_eq!(solve(&game, nodes).unwrap().nodes, nodes);
+        assert!(nodes <= UctConfig::default().endgame_nodes);
+    }
+
+    #[test]
+    fn test_deadline() {
+        let game = random_endgame(&mut StdRng::seed_from_u64(1), 12);
+        let nodes = solve(&game, u64::MAX).unwrap().nodes;
+
+        // The deadline is checked every few hundred positions.
+        assert_eq!(solve_until(&game, u64::MAX, Some(Instant::now())).is_none(), nodes >= 256);
+    }
+
+    #[test]
+    fn test_not_an_endgame() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(2));
+
+        assert!(solve(&game, u64::MAX).is_none());
+        assert!(solve_observation(&game.observation(game.to_move()), u64::MAX, None).is_none());
+    }
+
+    #[test]
+    fn test_solve_observation() {
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..10 {
+            let game = random_endgame(&mut rng, 6);
+            let player = game.to_move();
+            let solution = solve(&game, u64::MAX).unwrap();
+            let result = solve_observation(&game.observation(player), u64::MAX, None);
+
+            // Only won positions are reported, with the winning move as the single child.
+            if solution.winner == player && solution.best_action.is_some() {
+                let result = result.unwrap();
+
+                assert_eq!(result.children.len(), 1);
+                assert_eq!(result.best().action, solution.best_action);
+            } else {
+                assert!(result.is_none());
+            }
+        }
+    }
+}
+
+// Exact outcome of an endgame position.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    pub winner: Player,
+    // A move of the player to move keeping `winner`, or None if they have to pass or the game is over.
+    pub best_action: SearchAction,
+    // Positions searched.
+    pub nodes: u64,
+}
+
+// Bounds the memory of the solver when it is given no node limit.
+const MAX_TABLE_ENTRIES: usize = 1 << 16;
+
+// A solved position: its winner, and the cards left in both hands, which measure how much searching it took.
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    winner: Player,
+    cards: usize,
+}
+
+struct Solver {
+    // Solved positions by their Zobrist hash. A position with fewer cards left, quicker to solve again,
+    // does not replace one with more.
+    table: TranspositionTable<Entry>,
+    nodes: u64,
+    max_nodes: u64,
+    deadline: Option<Instant>,
+}
+impl Solver {
+    // Actions of the player to move, most promising first so that winning moves cut the search early.
+    fn ordered_actions(game: &Game) -> Vec<SearchAction> {
+        let mut actions = actions(game);
+        let player = game.to_move();
+
+        actions.sort_by_cached_key(|action| match action {
+            Some((card, stone)) => std::cmp::Reverse(greedy_score(game.board(), player, card, *stone)),
+            None => std::cmp::Reverse((0, 0)),
+        });
+
+        actions
+    }
+
+    // Winner of `game` with best play, where the player to move already claimed what they could.
+    // With only wins and losses, alpha-beta comes down to stopping at the first winning move.
+    // None if the node limit or the deadline was reached.
+    fn winner(&mut self, game: &Game) -> Option<(Player, SearchAction)> {
+        const CHECK_INTERVAL: u64 = 256;
+
+        self.nodes += 1;
+
+        if self.nodes > self.max_nodes { return None; }
+        if self.nodes % CHECK_INTERVAL == 0 && self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None;
+        }
+
+        let me = game.to_move();
+        let mut best = (me.get_other(), None);
+
+        for action in Self::ordered_actions(game) {
+            let mut child = game.clone();
+            apply(&mut child, &action);
+
+            let winner = match begin_turn(&mut child) {
+                Some(winner) => winner,
+                None => {
+                    let key = child.zobrist();
+
+                    match self.table.get(key) {
+                        Some(entry) => entry.winner,
+                        None => {
+                            let (winner, _) = self.winner(&child)?;
+                            let cards = child.hand(Player::build(0)).len() + child.hand(Player::build(1)).len();
+
+                            self.table.insert_unless(key, Entry { winner, cards }, |entry| entry.cards > cards);
+                            winner
+                        }
+                    }
+                }
+            };
+
+            if best.1.is_none() { best.1 = action.clone(); }
+
+            if winner == me {
+                best = (me, action);
+                break;
+            }
+        }
+
+        Some(best)
+    }
+}
+
+// Solves a position where the deck is empty, so both hands are known and the game is deterministic.
+// Returns None if the deck is not empty, or if more than `max_nodes` positions would be searched.
+pub fn solve(game: &Game, max_nodes: u64) -> Option<Solution> {
+    solve_until(game, max_nodes, None)
+}
+
+// Like `solve`, also giving up at `deadline`.
+pub fn solve_until(game: &Game, max_nodes: u64, deadline: Option<Instant>) -> Option<Solution> {
+    if game.deck_len() != 0 { return None; }
+
+    let mut game = game.clone();
+
+    if let Some(winner) = begin_turn(&mut game) {
+        return Some(Solution { winner, best_action: None, nodes: 0 });
+    }
+
+    // The solver stores at most one position per node.
+    let entries = max_nodes.saturating_mul(2).min(MAX_TABLE_ENTRIES as u64) as usize;
+    let mut solver = Solver { table: TranspositionTable::new(entries), nodes: 0, max_nodes, deadline };
+    let (winner, best_action) = solver.winner(&game)?;
+
+    Some(Solution { winner, best_action, nodes: solver.nodes })
+}
+
+// Lets a search player switch to exact play: once the deck is empty, the unseen cards are exactly
+// the opponent's hand. If the position is won, returns the winning move as the only root child.
+// Lost positions are left to the search, which plays on for the opponent's mistakes.
+// Searches with a time limit pass a deadline, leaving the rest of their time to the search if the solver gives up.
+pub fn solve_observation(observation: &Observation, max_nodes: u64, deadline: Option<Instant>) -> Option<SearchResult> {
+    if observation.deck_len() != 0 { return None; }
+
+    let solution = solve_until(&reveal(observation), max_nodes, deadline)?;
+
+    if solution.winner != observation.player || solution.best_action.is_none() { return None; }
+
+    Some(SearchResult {
+        player: observation.player,
+        children: vec![ChildStats::new(solution.best_action, 1, 1.0)],
+    })
+}
@@ -0,0 +1,134 @@
+// A fixed-size hash table from Zobrist hashes (see `crate::zobrist`) to search results.
+// Entries live in a slot chosen by the low bits of their key, and a new entry replaces whatever was there
+// (or, with `insert_unless`, whatever the caller does not want to keep), so memory stays bounded however long the search. Full keys are stored and compared, so a lookup misses entries
+// that were overwritten, and only returns another position's entry if both positions have the same 64 bit hash,
+// which is unlikely enough for searches to ignore.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get() {
+        let mut table = TranspositionTable::new(100);
+
+        assert_eq!(table.capacity(), 128);
+        assert!(table.is_empty());
+
+        table.insert(5, 'a');
+        table.insert(6, 'b');
+
+        assert_eq!(table.get(5), Some(&'a'));
+        assert_eq!(table.get(6), Some(&'b'));
+        assert_eq!(table.get(7), None);
+        assert_eq!(table.len(), 2);
+
+        table.insert(5, 'c');
+
+        assert_eq!(table.get(5), Some(&'c'));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn test_collision() {
+        // Both keys map to the same slot: the newer entry replaces the older,
+        // and the older key is not mistaken for the newer.
+        let mut table = TranspositionTable::new(16);
+        let (key1, key2) = (3, 3 + 16 * 1000);
+
+        table.insert(key1, 1);
+        table.insert(key2, 2);
+
+        assert_eq!(table.get(key1), None);
+        assert_eq!(table.get(key2), Some(&2));
+        assert_eq!(table.len(), 1);
+
+        table.clear();
+
+        assert_eq!(table.get(key2), None);
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_insert_unless() {
+        let mut table = TranspositionTable::new(16);
+        let (key1, key2) = (3, 3 + 16 * 1000);
+
+        table.insert(key1, 5);
+
+        // Another key's entry is kept if the caller says so.
+        table.insert_unless(key2, 2, |entry| *entry > 2);
+        assert_eq!(table.get(key1), Some(&5));
+        assert_eq!(table.get(key2), None);
+
+        table.insert_unless(key2, 7, |entry| *entry > 7);
+        assert_eq!(table.get(key1), None);
+        assert_eq!(table.get(key2), Some(&7));
+
+        // An entry for the same key is always replaced.
+        table.insert_unless(key2, 1, |_| true);
+        assert_eq!(table.get(key2), Some(&1));
+        assert_eq!(table.len(), 1);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TranspositionTable<T> {
+    slots: Vec<Option<(u64, T)>>,
+    len: usize,
+}
+impl<T> TranspositionTable<T> {
+    // A table with at least `entries` slots, rounded up to a power of two.
+    pub fn new(entries: usize) -> Self {
+        let capacity = entries.max(1).next_power_of_two();
+
+        TranspositionTable { slots: (0..capacity).map(|_| None).collect(), len: 0 }
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        key as usize & (self.slots.len() - 1)
+    }
+
+    pub fn get(&self, key: u64) -> Option<&T> {
+        match &self.slots[self.slot(key)] {
+            Some((stored, value)) if *stored == key => Some(value),
+            _ => None,
+        }
+    }
+
+    // Stores `value` for `key`, replacing the previous entry of the slot.
+    pub fn insert(&mut self, key: u64, value: T) {
+        let slot = self.slot(key);
+
+        if self.slots[slot].is_none() { self.len += 1; }
+
+        self.slots[slot] = Some((key, value));
+    }
+
+    // Stores `value` for `key`, unless the slot holds another key's entry and `keep` returns true for it.
+    pub fn insert_unless(&mut self, key: u64, value: T, keep: impl FnOnce(&T) -> bool) {
+        if let Some((stored, entry)) = &self.slots[self.slot(key)] {
+            if *stored != key && keep(entry) { return; }
+        }
+
+        self.insert(key, value);
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.len = 0;
+    }
+
+    // Number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+}
@@ -0,0 +1,526 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::determinize::{determinize, determinize_with_model};
+use super::opponent_model::OpponentModel;
+use super::rollout::{RolloutPolicy, UniformRollout};
+use super::transposition::TranspositionTable;
+use super::{actions, apply, begin_turn, share, simulate, solve_endgame, Budget, ChildStats, SearchAction, SearchResult, TreeStats};
+use crate::components::Player;
+use crate::observation::Observation;
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::board::cards_on_board::CardsOnBoard;
+    use crate::board::hand::Hand;
+    use crate::components::{Card, Stone};
+
+    #[test]
+    fn test_visits_add_up() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let game = Game::with_rng(&mut rng);
+        let config = UctConfig { iterations: 200, determinizations: 4, ..UctConfig::default() };
+
+        let result = search(&game.observation(game.to_move()), &config, &mut rng);
+
+        assert_eq!(result.player, game.to_move());
+        assert_eq!(result.children.iter().map(|child| child.visits).sum::<u32>(), 200);
+        assert!(result.children.len() <= game.legal_actions().len());
+    }
+
+    #[test]
+    fn test_no_virtual_loss() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(11));
+        let mut rng = StdRng::seed_from_u64(12);
+
+        // A child expanded by a simulation still running is not scored NaN.
+        let mut tree = Tree::new(&game, game.to_move(), 10);
+        let (path, _) = tree.select(&game, std::f64::consts::SQRT_2, 0, &mut rng);
+
+        assert_eq!(tree.ucb(0, path[1], std::f64::consts::SQRT_2), f64::INFINITY);
+
+        let config = UctConfig {
+            iterations: 301,
+            determinizations: 2,
+            threads: 4,
+            parallelism: Parallelism::Tree { virtual_loss: 0 },
+            ..UctConfig::default()
+        };
+
+        let result = search(&game.observation(game.to_move()), &config, &mut rng);
+
+        assert_eq!(result.children.iter().map(|child| child.visits).sum::<u32>(), 301);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(1));
+        let config = UctConfig { iterations: 100, determinizations: 2, ..UctConfig::default() };
+        let observation = game.observation(game.to_move());
+
+        let result1 = search(&observation, &config, &mut StdRng::seed_from_u64(2));
+        let result2 = search(&observation, &config, &mut StdRng::seed_from_u64(2));
+
+        assert_eq!(format!("{:?}", result1), format!("{:?}", result2));
+    }
+
+    #[test]
+    fn test_root_parallel_reproducible() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(5));
+        let observation = game.observation(game.to_move());
+        let config = UctConfig { iterations: 200, determinizations: 4, threads: 4, ..UctConfig::default() };
+
+        let result1 = search(&observation, &config, &mut StdRng::seed_from_u64(6));
+        let result2 = search(&observation, &config, &mut StdRng::seed_from_u64(6));
+
+        assert_eq!(format!("{:?}", result1), format!("{:?}", result2));
+        assert_eq!(result1.children.iter().map(|child| child.visits).sum::<u32>(), 200);
+
+        // With at least as many determinizations as threads, the number of threads does not matter.
+        for threads in [1, 3] {
+            let result = search(&observation, &UctConfig { threads, ..config.clone() }, &mut StdRng::seed_from_u64(6));
+
+            assert_eq!(format!("{:?}", result), format!("{:?}", result1));
+        }
+    }
+
+    #[test]
+    fn test_root_parallel_perfect_information() {
+        // A single determinization still gets a tree per thread.
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(7));
+        let config = UctConfig { iterations: 90, threads: 3, ..UctConfig::default() };
+
+        let result1 = search_perfect_information(&game, &config, &mut StdRng::seed_from_u64(8));
+        let result2 = search_perfect_information(&game, &config, &mut StdRng::seed_from_u64(8));
+
+        assert_eq!(format!("{:?}", result1), format!("{:?}", result2));
+        assert_eq!(result1.children.iter().map(|child| child.visits).sum::<u32>(), 90);
+    }
+
+    #[test]
+    fn test_tree_parallel() {
+        let game = Game::with_rng(&mut StdRng::seed_from_u64(9));
+        let config = UctConfig {
+            iterations: 301,
+            determinizations: 2,
+            threads: 4,
+            parallelism: Parallelism::Tree { virtual_loss: 3 },
+            ..UctConfig::default()
+        };
+
+        let result = search(&game.observation(game.to_move()), &config, &mut StdRng::seed_from_u64(10));
+
+        assert_eq!(result.children.iter().map(|child| child.visits).sum::<u32>(), 301);
+        assert!(result.children.len() <= game.legal_actions().len());
+    }
+
+    #[test]
+    fn test_transpositions() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut game = Game::with_rng(&mut rng);
+
+        // Late in the game, with few actions left, different move orders often meet.
+        while game.deck_len() > 4 {
+            let actions = game.legal_actions();
+            game.apply_action(actions[rng.gen_range(0..actions.len())]);
+            begin_turn(&mut game);
+        }
+
+        let config = UctConfig::default();
+        let mut tree = Tree::new(&game, game.to_move(), 2000);
+
+        for _ in 0..2000 {
+            tree.iterate(&game, &config, &mut rng);
+        }
+
+        let edges: usize = tree.nodes.iter().map(|node| node.children.len()).sum();
+
+        assert!(edges + 1 > tree.nodes.len());
+        assert_eq!(tree.root_stats().children.iter().map(|child| child.visits).sum::<u32>(), 2000);
+
+        // A shared node is counted once per visit, whichever parent it was reached from.
+        for (i, node) in tree.nodes.iter().enumerate().skip(1) {
+            let parents = tree.nodes.iter().filter(|parent| parent.children.iter().any(|(_, child)| *child == i)).count();
+
+            assert!(parents >= 1);
+            assert!(node.visits >= 1);
+        }
+    }
+
+    #[test]
+    fn test_defends_stone() {
+        // Player 2 won stones 1 and 2, and only 9 of Green saves stone 3 from being lost.
+        let mut board = CardsOnBoard::new();
+        let player = Player::build(0);
+        let other = player.get_other();
+
+        for (stone, color) in [(0, 1), (1, 2)] {
+            for num in 7..=9 { board.place_card(other, Stone::build(stone), Card::build(num, color)); }
+            board.place_card(player, Stone::build(stone), Card::build(4, color + 1));
+
+            assert!(board.claim(other, Stone::build(stone)));
+        }
+
+        for num in [6, 8, 9] { board.place_card(other, Stone::build(2), Card::build(num, 4)); }
+        for num in [7, 8] { board.place_card(player, Stone::build(2), Card::build(num, 5)); }
+
+        let mut hand = Hand::new();
+        for card in [Card::build(1,1), Card::build(2,2), Card::build(9,5), Card::build(3,3), Card::build(1,4), Card::build(2,6)] {
+            hand.add(card);
+        }
+
+        let observation = Observation::new(&hand, &board, player);
+        let config = UctConfig { iterations: 3000, determinizations: 3, ..UctConfig::default() };
+
+        let result = search(&observation, &config, &mut StdRng::seed_from_u64(3));
+
+        assert_eq!(result.best_action(&hand), Some((2, Stone::build(2))));
+
+        // Virtual losses spread the threads without hiding the only good move.
+        let config = UctConfig { threads: 4, parallelism: Parallelism::Tree { virtual_loss: 1 }, ..config };
+
+        let result = search(&observation, &config, &mut StdRng::seed_from_u64(3));
+
+        assert_eq!(result.best_action(&hand), Some((2, Stone::build(2))));
+    }
+}
+
+// How a search uses several threads.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Parallelism {
+    // Every thread grows its own trees, merged at the root. Results only depend on the seed
+    // and on the number of trees, which is the larger of `determinizations` and `threads`.
+    Root,
+    // The threads share the tree of each determinization. A thread going through a node adds `virtual_loss` losses
+    // to it until its simulation is back, steering the other threads elsewhere. Results vary between runs.
+    Tree { virtual_loss: u32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct UctConfig {
+    // Total iterations, split evenly between the determinizations.
+    pub iterations: u32,
+    pub determinizations: u32,
+    pub exploration: f64,
+    // Chooses the moves of simulations.
+    pub rollout: Arc<dyn RolloutPolicy>,
+    // Once the deck is empty, won positions are played exactly if the solver needs at most this many positions.
+    // 0 turns the solver off.
+    pub endgame_nodes: u64,
+    pub threads: usize,
+    pub parallelism: Parallelism,
+    // Thinking time per decision, split between the trees. With a limit, a tree also stops once its most visited move
+    // cannot be overtaken in the time left, and `iterations` only caps the search.
+    pub time_limit: Option<Duration>,
+}
+impl Default for UctConfig {
+    fn default() -> Self {
+        UctConfig {
+            iterations: 1000,
+            determinizations: 10,
+            exploration: std::f64::consts::SQRT_2,
+            rollout: Arc::new(UniformRollout),
+            endgame_nodes: 20000,
+            threads: 1,
+            parallelism: Parallelism::Root,
+            time_limit: None,
+        }
+    }
+}
+
+// Determinized UCT: runs an independent UCT search on each of several sampled full games,
+// and sums the statistics of the root children.
+pub fn search<R: Rng + ?Sized>(observation: &Observation, config: &UctConfig, rng: &mut R) -> SearchResult {
+    search_determinized(observation, config, rng, |rng| determinize(observation, rng))
+}
+
+// Like `search`, but the opponent's hand in each sampled game follows the beliefs of `model`.
+pub fn search_with_model<R: Rng + ?Sized>(
+    observation: &Observation,
+    config: &UctConfig,
+    model: &dyn OpponentModel,
+    rng: &mut R,
+) -> SearchResult {
+    search_determinized(observation, config, rng, |rng| determinize_with_model(observation, model, rng))
+}
+
+// UCT on the real game, seeing the opponent's hand and the order of the deck.
+// Not a fair player: it serves as an upper bound when comparing ways of handling hidden information.
+pub fn search_perfect_information<R: Rng + ?Sized>(game: &Game, config: &UctConfig, rng: &mut R) -> SearchResult {
+    let config = UctConfig { determinizations: 1, ..config.clone() };
+
+    search_determinized(&game.observation(game.to_move()), &config, rng, |_| game.clone())
+}
+
+fn search_determinized<R: Rng + ?Sized, F: FnMut(&mut R) -> Game>(
+    observation: &Observation,
+    config: &UctConfig,
+    rng: &mut R,
+    mut sample: F,
+) -> SearchResult {
+    let start = Instant::now();
+
+    if let Some(result) = solve_endgame(observation, config.endgame_nodes, config.time_limit, start) {
+        return result;
+    }
+
+    let player = observation.player;
+    let threads = config.threads.max(1);
+    let mut result = SearchResult { player, children: Vec::new() };
+
+    // The end of the time slice `i` out of `slices`, when the time limit is split between trees searched in turn.
+    let deadline = |i: u32, slices: u32| config.time_limit.map(|limit| start + limit * (i + 1) / slices);
+
+    match config.parallelism {
+        Parallelism::Root => {
+            let trees = config.determinizations.max(threads as u32);
+            // Trees searched in turn by each thread, see `parallel_map`.
+            let per_thread = trees.div_ceil(threads as u32);
+
+            // Games and seeds are drawn up front, so that threads do not change the result.
+            let jobs: Vec<(Game, u32, u64, Option<Instant>)> = (0..trees)
+                .map(|i| (sample(rng), share(config.iterations, trees, i), rng.gen(), deadline(i % per_thread, per_thread)))
+                .collect();
+
+            let stats = parallel_map(&jobs, threads, |(game, iterations, seed, deadline)| {
+                let mut rng = StdRng::seed_from_u64(*seed);
+                let mut tree = Tree::new(game, player, *iterations);
+                let budget = Budget::new(*iterations, *deadline, 1);
+                let mut done = 0;
+
+                while budget.proceed(done, || tree.root_visits()) {
+                    tree.iterate(game, config, &mut rng);
+                    done += 1;
+                }
+
+                tree.root_stats()
+            });
+
+            for stats in stats.iter() {
+                result.merge(stats);
+            }
+        }
+        Parallelism::Tree { virtual_loss } => {
+            let determinizations = config.determinizations.max(1);
+
+            for i in 0..determinizations {
+                let iterations = share(config.iterations, determinizations, i);
+                let deadline = deadline(i, determinizations);
+                let game = sample(rng);
+                let tree = Mutex::new(Tree::new(&game, player, iterations));
+                let seeds: Vec<u64> = (0..threads).map(|_| rng.gen()).collect();
+
+                thread::scope(|scope| {
+                    for (t, seed) in seeds.into_iter().enumerate() {
+                        let (tree, game) = (&tree, &game);
+
+                        scope.spawn(move || {
+                            let mut rng = StdRng::seed_from_u64(seed);
+                            let budget = Budget::new(share(iterations, threads as u32, t as u32), deadline, threads as u32);
+                            let mut done = 0;
+
+                            while budget.proceed(done, || tree.lock().expect("A search thread panicked.").root_visits()) {
+                                Tree::iterate_shared(tree, game, config, virtual_loss, &mut rng);
+                                done += 1;
+                            }
+                        });
+                    }
+                });
+
+                result.merge(&tree.into_inner().expect("A search thread panicked.").root_stats());
+            }
+        }
+    }
+
+    result
+}
+
+// Runs `job` on every item with up to `threads` threads, and returns the results in the order of `items`.
+fn parallel_map<T: Sync, U: Send, F: Fn(&T) -> U + Sync>(items: &[T], threads: usize, job: F) -> Vec<U> {
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(job).collect();
+    }
+
+    let job = &job;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(items.len().div_ceil(threads))
+            .map(|chunk| scope.spawn(move || chunk.iter().map(job).collect::<Vec<U>>()))
+            .collect();
+
+        handles.into_iter().flat_map(|handle| handle.join().expect("A search thread panicked.")).collect()
+    })
+}
+
+struct Node {
+    // The player who made the action leading to this node.
+    mover: Player,
+    // Actions and the nodes they lead to. Transpositions make several edges lead to the same node.
+    children: Vec<(SearchAction, usize)>,
+    untried: Vec<SearchAction>,
+    winner: Option<Player>,
+    visits: u32,
+    // Pending losses of threads simulating below this node, see `Parallelism::Tree`.
+    virtual_loss: u32,
+    // Sum of the rewards of `mover`.
+    value: f64,
+}
+
+// Bounds the memory of a tree when the search is limited by time rather than iterations.
+const MAX_TABLE_ENTRIES: usize = 1 << 16;
+
+// A UCT search graph over a single, fully known game. Positions reached by different move orders
+// share a node, found through a transposition table, so their statistics are pooled.
+struct Tree {
+    nodes: Vec<Node>,
+    table: TranspositionTable<usize>,
+}
+impl Tree {
+    fn new(game: &Game, player: Player, iterations: u32) -> Self {
+        let root = Node {
+            mover: player.get_other(),
+            children: Vec::new(),
+            untried: actions(game),
+            winner: game.winner(),
+            visits: 0,
+            virtual_loss: 0,
+            value: 0.0,
+        };
+
+        let mut table = TranspositionTable::new((2 * iterations as usize).min(MAX_TABLE_ENTRIES));
+        table.insert(game.zobrist(), 0);
+
+        Tree { nodes: vec![root], table }
+    }
+
+    // Virtual losses count as visits without reward. A child with neither, expanded by a thread whose simulation
+    // is not back yet, is tried first like any untried action.
+    fn ucb(&self, parent: usize, child: usize, exploration: f64) -> f64 {
+        let child = &self.nodes[child];
+        let parent = &self.nodes[parent];
+        let visits = (child.visits + child.virtual_loss) as f64;
+
+        if visits == 0.0 { return f64::INFINITY; }
+
+        child.value / visits + exploration * (((parent.visits + parent.virtual_loss) as f64).ln() / visits).sqrt()
+    }
+
+    fn iterate<R: Rng + ?Sized>(&mut self, root_game: &Game, config: &UctConfig, rng: &mut R) {
+        let (path, game) = self.select(root_game, config.exploration, 0, rng);
+
+        // Simulation
+        let winner = match game.winner() {
+            Some(winner) => winner,
+            None => simulate(game, config.rollout.as_ref(), rng),
+        };
+
+        self.backpropagate(&path, winner, 0);
+    }
+
+    // An iteration on a tree shared between threads. The lock is only held while selecting and backpropagating.
+    fn iterate_shared<R: Rng + ?Sized>(tree: &Mutex<Tree>, root_game: &Game, config: &UctConfig, virtual_loss: u32, rng: &mut R) {
+        let (path, game) = tree.lock().expect("A search thread panicked.").select(root_game, config.exploration, virtual_loss, rng);
+
+        let winner = match game.winner() {
+            Some(winner) => winner,
+            None => simulate(game, config.rollout.as_ref(), rng),
+        };
+
+        tree.lock().expect("A search thread panicked.").backpropagate(&path, winner, virtual_loss);
+    }
+
+    // Selection and expansion. Returns the path from the root, with the game at its end,
+    // after adding `virtual_loss` to every node of the path.
+    fn select<R: Rng + ?Sized>(&mut self, root_game: &Game, exploration: f64, virtual_loss: u32, rng: &mut R) -> (Vec<usize>, Game) {
+        let mut game = root_game.clone();
+        let mut path = vec![0];
+        let mut node = 0;
+
+        while self.nodes[node].winner.is_none() {
+            if !self.nodes[node].untried.is_empty() {
+                let index = rng.gen_range(0..self.nodes[node].untried.len());
+                let action = self.nodes[node].untried.swap_remove(index);
+
+                node = self.expand(node, &mut game, action);
+                path.push(node);
+                break;
+            }
+
+            let (action, child) = self.nodes[node]
+                .children
+                .iter()
+                .max_by(|a, b| self.ucb(node, a.1, exploration).total_cmp(&self.ucb(node, b.1, exploration)))
+                .cloned()
+                .expect("A non-terminal node should have children.");
+
+            apply(&mut game, &action);
+            begin_turn(&mut game);
+
+            node = child;
+            path.push(node);
+        }
+
+        for n in path.iter() {
+            self.nodes[*n].virtual_loss += virtual_loss;
+        }
+
+        (path, game)
+    }
+
+    // Backpropagation, along the path taken since a node may have several parents.
+    fn backpropagate(&mut self, path: &[usize], winner: Player, virtual_loss: u32) {
+        for n in path {
+            let node = &mut self.nodes[*n];
+
+            node.visits += 1;
+            node.virtual_loss -= virtual_loss;
+            if node.mover == winner { node.value += 1.0; }
+        }
+    }
+
+    // Adds the edge of `action` from `parent`, to a new node or to a known transposition.
+    fn expand(&mut self, parent: usize, game: &mut Game, action: SearchAction) -> usize {
+        let mover = game.to_move();
+
+        apply(game, &action);
+
+        let winner = begin_turn(game);
+        let key = game.zobrist();
+
+        let index = match self.table.get(key) {
+            Some(index) => *index,
+            None => {
+                let untried = if winner.is_none() { actions(game) } else { Vec::new() };
+
+                self.nodes.push(Node { mover, children: Vec::new(), untried, winner, visits: 0, virtual_loss: 0, value: 0.0 });
+                self.table.insert(key, self.nodes.len() - 1);
+
+                self.nodes.len() - 1
+            }
+        };
+
+        self.nodes[parent].children.push((action, index));
+
+        index
+    }
+}
+impl TreeStats for Tree {
+    fn root_player(&self) -> Player { self.nodes[0].mover.get_other() }
+
+    fn edges(&self, node: usize) -> Vec<(&SearchAction, usize)> {
+        self.nodes[node].children.iter().map(|(action, child)| (action, *child)).collect()
+    }
+
+    fn node_stats(&self, action: &SearchAction, node: usize) -> ChildStats {
+        ChildStats::new(action.clone(), self.nodes[node].visits, self.nodes[node].value)
+    }
+}
@@ -0,0 +1,320 @@
+// Self-play data generation: search bots play against themselves, and every decision is recorded
+// with the search's visit distribution and the final outcome of the game, for training networks offline.
+//
+// Records are written as JSON lines, split into shards of `positions_per_shard` records
+// named shard_00000.jsonl, shard_00001.jsonl, ... Each line holds:
+//
+// Key        | Value
+// -----------|------------------------------------------------------------------------------
+// game       | Index of the game
+// ply        | Index of the decision in the game
+// player     | Player to move (1 or 2)
+// hand       | Hand of the player to move, in protocol notation
+// board      | Board notation (see `CardsOnBoard::notation`)
+// encoding   | Encoding of the player's observation (see the `encode` module)
+// policy     | Root visit distribution, indexed by `encode::action_index`
+// outcome    | 1 if the player to move won the game, -1 otherwise
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::components::Player;
+use crate::encode::{action_from_index, encode};
+use crate::protocol::hand_notation;
+use crate::search::begin_turn;
+use crate::search::uct::{search, UctConfig};
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SelfPlayConfig {
+        SelfPlayConfig {
+            search: UctConfig { iterations: 20, determinizations: 2, ..UctConfig::default() },
+            games: 2,
+            positions_per_shard: 25,
+            sample_plies: 4,
+            seed: 0,
+        }
+    }
+
+    #[test]
+    fn test_play_game() {
+        let records = play_game(&config(), 0);
+
+        assert!(!records.is_empty());
+
+        for (ply, record) in records.iter().enumerate() {
+            assert_eq!(record.ply, ply);
+            assert!((record.policy.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+            assert!(record.outcome == 1.0 || record.outcome == -1.0);
+        }
+
+        // Both players share the outcome of the game, from their own points of view.
+        let first = records.iter().find(|r| r.player == Player::build(0)).unwrap();
+        let second = records.iter().find(|r| r.player == Player::build(1)).unwrap();
+
+        assert_eq!(first.outcome, -second.outcome);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let records1 = play_game(&config(), 1);
+        let records2 = play_game(&config(), 1);
+
+        assert_eq!(records1.len(), records2.len());
+        assert_eq!(records1.last().unwrap().board, records2.last().unwrap().board);
+    }
+
+    #[test]
+    fn test_record_json() {
+        let record = Record {
+            game: 3,
+            ply: 7,
+            player: Player::build(1),
+            hand: String::from("Pu1,Re2"),
+            board: String::from("//////// //////// --------- ---------"),
+            encoding: vec![0.0, 1.0, 0.5],
+            policy: vec![0.25, 0.75],
+            outcome: -1.0,
+        };
+
+        let mut output = Vec::new();
+        write_record(&mut output, &record).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"game\":3,\"ply\":7,\"player\":2,\"hand\":\"Pu1,Re2\",\"board\":\"//////// //////// --------- ---------\",\
+            \"encoding\":[0,1,0.5],\"policy\":[0.25,0.75],\"outcome\":-1}\n"
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("Pu1,Re2"), "\"Pu1,Re2\"");
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = std::env::temp_dir().join(format!("schotten_selfplay_{}", std::process::id()));
+
+        let positions = run(&config(), &dir).unwrap();
+
+        let mut lines = 0;
+        let mut shards: Vec<PathBuf> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        shards.sort();
+
+        for (i, shard) in shards.iter().enumerate() {
+            assert_eq!(shard.file_name().unwrap().to_str().unwrap(), format!("shard_{:05}.jsonl", i));
+
+            let shard_lines = fs::read_to_string(shard).unwrap().lines().count();
+
+            assert!(shard_lines <= 25);
+            lines += shard_lines;
+        }
+
+        assert_eq!(lines, positions);
+        assert_eq!(shards.len(), positions.div_ceil(25));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SelfPlayConfig {
+    pub search: UctConfig,
+    pub games: usize,
+    pub positions_per_shard: usize,
+    // Moves are sampled in proportion to visits during the first plies of a game, for variety between games.
+    // Afterwards, the most visited move is played.
+    pub sample_plies: usize,
+    // Game i is played with the seed `seed + i`.
+    pub seed: u64,
+}
+impl Default for SelfPlayConfig {
+    fn default() -> Self {
+        SelfPlayConfig {
+            search: UctConfig::default(),
+            games: 100,
+            positions_per_shard: 10000,
+            sample_plies: 10,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub game: usize,
+    pub ply: usize,
+    pub player: Player,
+    pub hand: String,
+    pub board: String,
+    pub encoding: Vec<f32>,
+    pub policy: Vec<f32>,
+    pub outcome: f32,
+}
+
+pub fn play_game(config: &SelfPlayConfig, game_index: usize) -> Vec<Record> {
+    let mut rng = StdRng::seed_from_u64(config.seed.wrapping_add(game_index as u64));
+    let mut game = Game::with_rng(&mut rng);
+    let mut records: Vec<Record> = Vec::new();
+
+    let winner = loop {
+        if let Some(winner) = begin_turn(&mut game) {
+            break winner;
+        }
+
+        if game.legal_actions().is_empty() {
+            game.pass();
+            continue;
+        }
+
+        let player = game.to_move();
+        let observation = game.observation(player);
+        let result = search(&observation, &config.search, &mut rng);
+        let policy = result.visit_distribution(observation.hand);
+
+        let action = if records.len() < config.sample_plies {
+            sample(&policy, &mut rng)
+        } else {
+            result.best_action(observation.hand).expect("A player with a legal action should not pass.")
+        };
+
+        records.push(Record {
+            game: game_index,
+            ply: records.len(),
+            player,
+            hand: hand_notation(observation.hand),
+            board: observation.board.notation(),
+            encoding: encode(&observation),
+            policy,
+            outcome: 0.0,
+        });
+
+        game.apply_action(action);
+    };
+
+    for record in records.iter_mut() {
+        record.outcome = if record.player == winner { 1.0 } else { -1.0 };
+    }
+
+    records
+}
+
+fn sample<R: Rng + ?Sized>(policy: &[f32], rng: &mut R) -> (usize, crate::components::Stone) {
+    let mut remaining = rng.gen::<f32>() * policy.iter().sum::<f32>();
+
+    for (index, p) in policy.iter().enumerate() {
+        if *p > 0.0 && remaining < *p {
+            return action_from_index(index);
+        }
+        remaining -= p;
+    }
+
+    // Rounding errors may leave a tiny remainder, which belongs to the last action with visits.
+    action_from_index(policy.iter().rposition(|p| *p > 0.0).expect("A policy should have visited actions."))
+}
+
+// A JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+pub fn write_record<W: Write>(output: &mut W, record: &Record) -> io::Result<()> {
+    // Encodings and policies are finite, so every value is a valid JSON number.
+    let floats = |values: &[f32]| values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",");
+
+    writeln!(
+        output,
+        "{{\"game\":{},\"ply\":{},\"player\":{},\"hand\":{},\"board\":{},\"encoding\":[{}],\"policy\":[{}],\"outcome\":{}}}",
+        record.game,
+        record.ply,
+        record.player.get_player(),
+        json_string(&record.hand),
+        json_string(&record.board),
+        floats(&record.encoding),
+        floats(&record.policy),
+        record.outcome,
+    )
+}
+
+// Writes records to numbered shard files, starting a new shard every `positions_per_shard` records.
+pub struct ShardWriter {
+    dir: PathBuf,
+    positions_per_shard: usize,
+    shard: Option<BufWriter<File>>,
+    shards: usize,
+    positions: usize,
+}
+impl ShardWriter {
+    pub fn new(dir: &Path, positions_per_shard: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+
+        Ok(ShardWriter {
+            dir: dir.to_path_buf(),
+            positions_per_shard: positions_per_shard.max(1),
+            shard: None,
+            shards: 0,
+            positions: 0,
+        })
+    }
+
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        if self.positions % self.positions_per_shard == 0 {
+            self.finish()?;
+
+            let path = self.dir.join(format!("shard_{:05}.jsonl", self.shards));
+
+            self.shard = Some(BufWriter::new(File::create(path)?));
+            self.shards += 1;
+        }
+
+        write_record(self.shard.as_mut().expect("A shard was opened above."), record)?;
+        self.positions += 1;
+
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self.shard.take() {
+            Some(mut shard) => shard.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn positions(&self) -> usize { self.positions }
+}
+
+// Plays `config.games` games and writes their records to `dir`. Returns the number of records written.
+pub fn run(config: &SelfPlayConfig, dir: &Path) -> io::Result<usize> {
+    let mut writer = ShardWriter::new(dir, config.positions_per_shard)?;
+
+    for game_index in 0..config.games {
+        for record in play_game(config, game_index) {
+            writer.write(&record)?;
+        }
+    }
+
+    writer.finish()?;
+
+    Ok(writer.positions())
+}
@@ -0,0 +1,303 @@
+// Symmetries of Schotten Totten. Colors only matter through whether cards share one, so any permutation
+// of the six colors maps a position to an equivalent one. Reversing the order of the stones does too,
+// since both ways to win (five stones, or three adjacent stones) read the same from either end.
+// Together these make 720 * 2 symmetries.
+//
+// A canonical form picks one representative of every class of equivalent positions:
+// the image with the smallest key among all symmetries. It serves transposition tables, opening books,
+// and `Symmetry::all` serves training data augmentation.
+
+use itertools::Itertools;
+
+use crate::board::cards_on_board::CardsOnBoard;
+use crate::board::deck::Deck;
+use crate::board::hand::Hand;
+use crate::common::{NUM_OF_COLORS, NUM_OF_PLAYERS, NUM_OF_STONES};
+use crate::components::{Card, Player, Stone};
+use crate::encode::card_index;
+use crate::observation::Observation;
+use crate::Game;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use crate::search::begin_turn;
+
+    fn random_game(rng: &mut StdRng) -> Game {
+        let mut game = Game::with_rng(rng);
+        let plies = rng.gen_range(0..40);
+
+        for _ in 0..plies {
+            if begin_turn(&mut game).is_some() { break; }
+
+            let actions = game.legal_actions();
+
+            if actions.is_empty() {
+                game.pass();
+            } else {
+                game.apply_action(actions[rng.gen_range(0..actions.len())]);
+            }
+        }
+
+        game
+    }
+
+    fn random_symmetry(rng: &mut StdRng) -> Symmetry {
+        let mut colors: Vec<u8> = (1..=NUM_OF_COLORS).collect();
+        colors.shuffle(rng);
+
+        Symmetry::build(colors.try_into().unwrap(), rng.gen())
+    }
+
+    // Everything about a game, as a string.
+    fn describe(game: &Game) -> String {
+        let deck: Vec<String> = (0..game.deck.len()).map(|i| game.deck[i].to_string()).collect();
+
+        format!("{} | {} | {} | {} | {}", game.board.notation(), game.hand1, game.hand2, deck.join(","), game.to_move.get_player())
+    }
+
+    #[test]
+    fn test_all() {
+        let all: HashSet<Symmetry> = Symmetry::all().collect();
+
+        assert_eq!(all.len(), 1440);
+        assert!(all.contains(&Symmetry::identity()));
+
+        for symmetry in all.iter() {
+            let inverse = symmetry.inverse();
+
+            for card in (1..=NUM_OF_COLORS).map(|color| Card::build(5, color)) {
+                assert_eq!(inverse.card(&symmetry.card(&card)), card);
+            }
+
+            for stone in (0..NUM_OF_STONES).map(Stone::build) {
+                assert_eq!(inverse.stone(symmetry.stone(stone)), stone);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_build_not_a_permutation() {
+        Symmetry::build([1, 2, 3, 4, 5, 5], false);
+    }
+
+    #[test]
+    fn test_preserves_rules() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..30 {
+            let game = random_game(&mut rng);
+            let symmetry = random_symmetry(&mut rng);
+            let board = symmetry.board(&game.board);
+
+            assert_eq!(board.terminal_state(), game.board.terminal_state());
+
+            for player in (0..NUM_OF_PLAYERS).map(Player::build) {
+                for stone in (0..NUM_OF_STONES).map(Stone::build) {
+                    let (image, cards) = (board.cards_of(player, symmetry.stone(stone)), game.board.cards_of(player, stone));
+
+                    assert_eq!(board.is_legal_claim(player, symmetry.stone(stone)), game.board.is_legal_claim(player, stone));
+                    if cards.is_full() { assert_eq!(image.strength(), cards.strength()); }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_game() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..20 {
+            let game = random_game(&mut rng);
+            let permuted = random_symmetry(&mut rng).game(&game);
+
+            let (canonical1, symmetry1) = canonical_game(&game);
+            let (canonical2, _) = canonical_game(&permuted);
+
+            assert_eq!(describe(&canonical1), describe(&canonical2));
+            assert_eq!(canonical1.zobrist(), canonical2.zobrist());
+            assert_eq!(describe(&canonical1), describe(&symmetry1.game(&game)));
+
+            // The canonical form is its own canonical form.
+            assert_eq!(describe(&canonical_game(&canonical1).0), describe(&canonical1));
+        }
+    }
+
+    #[test]
+    fn test_canonical_observation() {
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for _ in 0..20 {
+            let game = random_game(&mut rng);
+            let player = game.to_move;
+            let symmetry = random_symmetry(&mut rng);
+            let (board, hand) = (symmetry.board(&game.board), symmetry.hand(game.hand(player)));
+
+            let (board1, hand1, _) = canonical_observation(&game.observation(player));
+            let (board2, hand2, _) = canonical_observation(&Observation::new(&hand, &board, player));
+
+            assert_eq!(board1.notation(), board2.notation());
+            assert_eq!(hand1.to_string(), hand2.to_string());
+        }
+    }
+
+    #[test]
+    fn test_distinct_positions() {
+        // Colors matter through whether cards share one: a color run is not equivalent to a run.
+        let mut board1 = CardsOnBoard::new();
+        let mut board2 = CardsOnBoard::new();
+
+        for num in 1..=3 {
+            board1.place_card(Player::build(0), Stone::build(0), Card::build(num, 1));
+            board2.place_card(Player::build(0), Stone::build(0), Card::build(num, num));
+        }
+
+        let hand = Hand::new();
+        let player = Player::build(0);
+
+        let (board1, _, _) = canonical_observation(&Observation::new(&hand, &board1, player));
+        let (board2, _, _) = canonical_observation(&Observation::new(&hand, &board2, player));
+
+        assert_ne!(board1.notation(), board2.notation());
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Symmetry {
+    // Color c becomes colors[c - 1].
+    colors: [u8; NUM_OF_COLORS as usize],
+    // Whether stone i becomes stone NUM_OF_STONES - 1 - i.
+    mirrored: bool,
+}
+impl Symmetry {
+    pub fn identity() -> Self {
+        Self::build([1, 2, 3, 4, 5, 6], false)
+    }
+
+    pub fn build(colors: [u8; NUM_OF_COLORS as usize], mirrored: bool) -> Self {
+        if !(1..=NUM_OF_COLORS).all(|color| colors.contains(&color)) {
+            panic!("Expected a permutation of the colors, got {:?}", colors);
+        }
+
+        Symmetry { colors, mirrored }
+    }
+
+    // Every symmetry of the game, starting with the identity.
+    pub fn all() -> impl Iterator<Item = Symmetry> {
+        [false, true].into_iter().flat_map(|mirrored| {
+            (1..=NUM_OF_COLORS)
+                .permutations(NUM_OF_COLORS as usize)
+                .map(move |colors| Symmetry::build(colors.try_into().expect("Permutations have every color."), mirrored))
+        })
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut colors = [0; NUM_OF_COLORS as usize];
+
+        for (index, color) in self.colors.iter().enumerate() {
+            colors[(*color - 1) as usize] = index as u8 + 1;
+        }
+
+        Symmetry { colors, mirrored: self.mirrored }
+    }
+
+    pub fn card(&self, card: &Card) -> Card {
+        Card::build(card.num(), self.colors[card.color_index()])
+    }
+
+    pub fn stone(&self, stone: Stone) -> Stone {
+        if self.mirrored { Stone::build(NUM_OF_STONES - 1 - stone.get_index() as u8) } else { stone }
+    }
+
+    // Cards on each stone end up sorted.
+    pub fn board(&self, board: &CardsOnBoard) -> CardsOnBoard {
+        board.relabeled(|card| self.card(card), |stone| self.stone(stone))
+    }
+
+    // The hand ends up sorted, so hand indices of actions change.
+    pub fn hand(&self, hand: &Hand) -> Hand {
+        let mut result = Hand::new();
+
+        for card in hand.iter().map(|card| self.card(card)).sorted_by_key(card_index) {
+            result.add(card);
+        }
+
+        result
+    }
+
+    // Also keeps the order of the deck, with its cards relabeled.
+    pub fn game(&self, game: &Game) -> Game {
+        Game {
+            board: self.board(&game.board),
+            deck: Deck::from_cards((0..game.deck.len()).map(|i| self.card(&game.deck[i])).collect()),
+            hand1: self.hand(&game.hand1),
+            hand2: self.hand(&game.hand2),
+            to_move: game.to_move,
+            auto_claim: game.auto_claim,
+        }
+    }
+
+    // Compared between symmetries to find the canonical one: the cards, claims and advantage of every stone,
+    // the cards of every hand and then the deck in order, all as they would be after applying the symmetry.
+    fn key(&self, board: &CardsOnBoard, hands: &[&Hand], deck: &[Card]) -> Vec<u8> {
+        const SEPARATOR: u8 = u8::MAX;
+
+        let player_code = |player: Option<Player>| player.map_or(0, |p| p.get_player());
+        let mut key = Vec::with_capacity(128);
+
+        for stone in (0..NUM_OF_STONES).map(Stone::build) {
+            // Mirroring is its own inverse.
+            let source = self.stone(stone);
+
+            for player in (0..NUM_OF_PLAYERS).map(Player::build) {
+                let cards = board.cards_of(player, source).iter().map(|card| card_index(&self.card(card)) as u8).sorted();
+
+                key.extend(cards);
+                key.push(SEPARATOR);
+            }
+
+            key.push(player_code(board.who_claimed(source)));
+            key.push(player_code(board.advantage(source)));
+        }
+
+        for hand in hands {
+            key.extend(hand.iter().map(|card| card_index(&self.card(card)) as u8).sorted());
+            key.push(SEPARATOR);
+        }
+
+        key.extend(deck.iter().map(|card| card_index(&self.card(card)) as u8));
+
+        key
+    }
+}
+
+// The symmetry taking the board and hands to their canonical form.
+pub fn canonical_symmetry(board: &CardsOnBoard, hands: &[&Hand]) -> Symmetry {
+    canonical_by(|symmetry| symmetry.key(board, hands, &[]))
+}
+
+fn canonical_by<F: Fn(&Symmetry) -> Vec<u8>>(key: F) -> Symmetry {
+    Symmetry::all().min_by_key(key).expect("There is at least the identity.")
+}
+
+// The canonical form of what a player sees, with the symmetry leading to it.
+pub fn canonical_observation(observation: &Observation) -> (CardsOnBoard, Hand, Symmetry) {
+    let symmetry = canonical_symmetry(observation.board, &[observation.hand]);
+
+    (symmetry.board(observation.board), symmetry.hand(observation.hand), symmetry)
+}
+
+// The canonical form of a game, with the symmetry leading to it. The deck only breaks ties
+// between symmetries leaving the board and hands the same.
+pub fn canonical_game(game: &Game) -> (Game, Symmetry) {
+    let deck: Vec<Card> = (0..game.deck.len()).map(|i| game.deck[i].clone()).collect();
+    let symmetry = canonical_by(|symmetry| symmetry.key(&game.board, &[&game.hand1, &game.hand2], &deck));
+
+    (symmetry.game(game), symmetry)
+}
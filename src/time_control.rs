@@ -0,0 +1,196 @@
+// Clocks for games between bots, e.g. in tournaments.
+//
+// The game loop (`Game::play_with_clock`) keeps a `Clock` for both players, charges each turn to the player to move,
+// and tells players their `TimeLeft` when asking for a move. A player who runs out of time loses.
+// Search players split their time with `TimeLeft::allocate`, spending more where more moves are left to play.
+
+use std::time::Duration;
+
+use crate::board::cards_on_board::CardsOnBoard;
+use crate::common::{CARDS_IN_DECK, CARDS_IN_HAND, NUM_OF_PLAYERS, NUM_OF_STONES, STONE_CARDS_LIMIT};
+use crate::components::{Player, Stone};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::components::Card;
+
+    #[test]
+    fn test_clock_fischer() {
+        let control = TimeControl::Fischer { base: Duration::from_secs(10), increment: Duration::from_secs(1) };
+        let mut clock = Clock::new(control);
+        let player = Player::build(0);
+
+        assert!(clock.charge(player, Duration::from_secs(3)));
+        assert_eq!(clock.time_left(player).remaining, Duration::from_secs(8));
+        assert_eq!(clock.time_left(player.get_other()).remaining, Duration::from_secs(10));
+
+        assert!(!clock.charge(player, Duration::from_secs(9)));
+        assert_eq!(clock.time_left(player).remaining, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_clock_total_and_per_move() {
+        let mut clock = Clock::new(TimeControl::Total(Duration::from_secs(5)));
+        let player = Player::build(1);
+
+        assert!(clock.charge(player, Duration::from_secs(2)));
+        assert!(clock.charge(player, Duration::from_secs(2)));
+        assert_eq!(clock.time_left(player).remaining, Duration::from_secs(1));
+        assert!(!clock.charge(player, Duration::from_secs(2)));
+
+        let mut clock = Clock::new(TimeControl::PerMove(Duration::from_secs(1)));
+
+        assert!(clock.charge(player, Duration::from_millis(900)));
+        assert!(clock.time_left(player).per_move);
+        assert_eq!(clock.time_left(player).remaining, Duration::from_secs(1));
+        assert!(!clock.charge(player, Duration::from_millis(1100)));
+    }
+
+    #[test]
+    fn test_moves_left() {
+        let player = Player::build(0);
+        let mut board = CardsOnBoard::new();
+
+        assert_eq!(expected_moves_left(&board, player), CARDS_IN_HAND as u32 + 21);
+
+        // Late in the game, the open stones limit the moves left.
+        for stone in (0..8).map(Stone::build) {
+            for num in 1..=3 {
+                let index = stone.get_index() as u8;
+
+                board.place_card(player, stone, Card::build(num + 3 * (index / 6), index % 6 + 1));
+            }
+        }
+
+        assert_eq!(expected_moves_left(&board, player), 3);
+    }
+
+    #[test]
+    fn test_allocate() {
+        let board = CardsOnBoard::new();
+        let player = Player::build(0);
+
+        let per_move = TimeLeft { remaining: Duration::from_secs(1), increment: Duration::ZERO, per_move: true };
+        assert!(per_move.allocate(&board, player) < Duration::from_secs(1));
+
+        let total = TimeLeft { remaining: Duration::from_secs(21), increment: Duration::ZERO, per_move: false };
+        let early = total.allocate(&board, player);
+
+        assert!(early > Duration::from_millis(500) && early < Duration::from_secs(1));
+
+        // However large the increment, a move never takes more than half the clock.
+        let fischer = TimeLeft { remaining: Duration::from_millis(100), increment: Duration::from_secs(1), per_move: false };
+
+        assert!(fischer.allocate(&board, player) <= Duration::from_millis(50));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeControl {
+    // Every move may take up to this long.
+    PerMove(Duration),
+    // Each player starts with `base`, and gains `increment` after each of their moves.
+    Fischer { base: Duration, increment: Duration },
+    // Each player has this long for the whole game.
+    Total(Duration),
+}
+
+// What a player knows of their clock when asked for a move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeLeft {
+    // Time left on the clock, or the limit of this move with a per move control.
+    pub remaining: Duration,
+    // Added to the clock after the move.
+    pub increment: Duration,
+    // Whether `remaining` is for this move only.
+    pub per_move: bool,
+}
+impl TimeLeft {
+    // Time to think about the next move. The time on the clock is split between the moves still expected
+    // (see `expected_moves_left`), with most of the increment on top. A margin covers the time
+    // spent outside of the search, and a single move never takes more than half the clock.
+    pub fn allocate(&self, board: &CardsOnBoard, player: Player) -> Duration {
+        const MARGIN: f64 = 0.9;
+
+        if self.per_move {
+            return self.remaining.mul_f64(MARGIN);
+        }
+
+        let moves = expected_moves_left(board, player);
+        let share = self.remaining / moves + self.increment.mul_f64(MARGIN);
+
+        share.min(self.remaining / 2).mul_f64(MARGIN)
+    }
+}
+
+// Placements `player` can still expect to make: the cards they will hold, unless the open stones run out first.
+pub fn expected_moves_left(board: &CardsOnBoard, player: Player) -> u32 {
+    let dealt = (NUM_OF_PLAYERS * CARDS_IN_HAND) as usize;
+    let deck = (CARDS_IN_DECK as usize - dealt).saturating_sub(board.num_of_cards());
+
+    let open_slots: usize = (0..NUM_OF_STONES)
+        .map(Stone::build)
+        .filter(|stone| board.who_claimed(*stone).is_none())
+        .map(|stone| STONE_CARDS_LIMIT as usize - board.cards_of(player, stone).len())
+        .sum();
+
+    let cards = CARDS_IN_HAND as usize + deck / NUM_OF_PLAYERS as usize;
+
+    cards.min(open_slots).max(1) as u32
+}
+
+// The clocks of both players.
+#[derive(Clone, Debug)]
+pub struct Clock {
+    control: TimeControl,
+    remaining: [Duration; NUM_OF_PLAYERS as usize],
+}
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let start = match control {
+            TimeControl::PerMove(limit) => limit,
+            TimeControl::Fischer { base, .. } => base,
+            TimeControl::Total(total) => total,
+        };
+
+        Clock { control, remaining: [start; NUM_OF_PLAYERS as usize] }
+    }
+
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    pub fn time_left(&self, player: Player) -> TimeLeft {
+        let increment = match self.control {
+            TimeControl::Fischer { increment, .. } => increment,
+            _ => Duration::ZERO,
+        };
+
+        TimeLeft {
+            remaining: self.remaining[player.get_index()],
+            increment,
+            per_move: matches!(self.control, TimeControl::PerMove(_)),
+        }
+    }
+
+    // Charges a turn of `player` that took `elapsed`. Returns false if they ran out of time.
+    pub fn charge(&mut self, player: Player, elapsed: Duration) -> bool {
+        let remaining = &mut self.remaining[player.get_index()];
+
+        if elapsed > *remaining {
+            if !matches!(self.control, TimeControl::PerMove(_)) { *remaining = Duration::ZERO; }
+
+            return false;
+        }
+
+        match self.control {
+            TimeControl::PerMove(_) => (),
+            TimeControl::Fischer { increment, .. } => *remaining = *remaining - elapsed + increment,
+            TimeControl::Total(_) => *remaining -= elapsed,
+        }
+
+        true
+    }
+}
@@ -0,0 +1,101 @@
+// Zobrist keys: every feature of a position gets a fixed random key, and a position hashes to the XOR
+// of the keys of its features. Placing a card or claiming a stone then updates the hash with a single XOR,
+// which `CardsOnBoard` and `Hand` do as they change.
+//
+// Feature                              | Key
+// -------------------------------------|------------------------------
+// Card on a stone, by player           | card_key(player, stone, card)
+// Claimed stone, by player             | claim_key(player, stone)
+// Completed first (advantage), by player | advantage_key(player, stone)
+// Card in hand                         | hand_key(card)
+// Player 2 to move                     | to_move_key(player)
+//
+// Both hands use the same keys, so the hash of the second hand is rotated before combining (see `Game::zobrist`).
+
+use crate::common::{CARDS_IN_DECK, NUM_OF_PLAYERS, NUM_OF_STONES};
+use crate::components::{Card, Player, Stone};
+use crate::encode::card_index;
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_keys_are_distinct() {
+        let mut keys = HashSet::new();
+
+        for key in CARD_KEYS.iter().chain(CLAIM_KEYS.iter()).chain(ADVANTAGE_KEYS.iter()).chain(HAND_KEYS.iter()) {
+            assert!(keys.insert(*key));
+        }
+
+        assert!(keys.insert(TO_MOVE_KEY));
+        assert!(keys.insert(second_hand(HAND_KEYS[0])));
+        assert!(!keys.contains(&0));
+    }
+}
+
+const CARDS: usize = CARDS_IN_DECK as usize;
+const PLAYERS: usize = NUM_OF_PLAYERS as usize;
+const STONES: usize = NUM_OF_STONES as usize;
+
+// SplitMix64 step, returns the next state and output.
+const fn split_mix(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    (state, z ^ (z >> 31))
+}
+
+// Keys are generated at compile time, so hashes are the same across runs and builds.
+const fn keys<const N: usize>(seed: u64) -> [u64; N] {
+    let mut keys = [0; N];
+    let mut state = seed;
+    let mut i = 0;
+
+    while i < N {
+        let (next, key) = split_mix(state);
+
+        state = next;
+        keys[i] = key;
+        i += 1;
+    }
+
+    keys
+}
+
+const CARD_KEYS: [u64; PLAYERS * STONES * CARDS] = keys(1);
+const CLAIM_KEYS: [u64; PLAYERS * STONES] = keys(2);
+const ADVANTAGE_KEYS: [u64; PLAYERS * STONES] = keys(3);
+const HAND_KEYS: [u64; CARDS] = keys(4);
+const TO_MOVE_KEY: u64 = keys::<1>(5)[0];
+
+pub fn card_key(player: Player, stone: Stone, card: &Card) -> u64 {
+    CARD_KEYS[(player.get_index() * STONES + stone.get_index()) * CARDS + card_index(card)]
+}
+
+pub fn claim_key(player: Player, stone: Stone) -> u64 {
+    CLAIM_KEYS[player.get_index() * STONES + stone.get_index()]
+}
+
+pub fn advantage_key(player: Player, stone: Stone) -> u64 {
+    ADVANTAGE_KEYS[player.get_index() * STONES + stone.get_index()]
+}
+
+pub fn hand_key(card: &Card) -> u64 {
+    HAND_KEYS[card_index(card)]
+}
+
+pub fn to_move_key(player: Player) -> u64 {
+    if player.get_index() == 0 { 0 } else { TO_MOVE_KEY }
+}
+
+// Turns the hash of a hand into the hash of the same hand held by the second player.
+pub fn second_hand(hash: u64) -> u64 {
+    hash.rotate_left(32)
+}
@@ -0,0 +1,48 @@
+// Playouts never touch the heap. The counting allocator replaces the global allocator of this test binary only.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use schotten::search::playout::Playout;
+use schotten::Game;
+
+// Counts the allocations of each thread, so that tests running in parallel do not count each other's.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_no_allocation() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let allocations = ALLOCATIONS.with(Cell::get);
+    let playouts: Vec<Playout> = (0..20).map(|_| Playout::new(&Game::with_rng(&mut rng))).collect();
+
+    // Allocations are counted.
+    let before = ALLOCATIONS.with(Cell::get);
+    assert!(before > allocations);
+
+    for playout in playouts.iter() {
+        playout.play(&mut rng);
+    }
+
+    assert_eq!(ALLOCATIONS.with(Cell::get), before);
+}